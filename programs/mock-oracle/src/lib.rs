@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+
+declare_id!("MockoracLeProgram11111111111111111111111111");
+
+/// 本地测试用的模拟预言机
+/// 结果账户的前四个字段(round, winner_team, updated_at, authority)即博彩合约约定的预言机结果布局
+#[program]
+pub mod mock_oracle {
+    use super::*;
+
+    /// 发布轮次结果
+    /// 首次发布时创建结果账户并记录发布者，之后只有发布者可以更新
+    pub fn publish_result(
+        ctx: Context<PublishResult>,
+        round: Pubkey,
+        winner_team: Pubkey,
+    ) -> Result<()> {
+        let result = &mut ctx.accounts.oracle_result;
+        if result.authority == Pubkey::default() {
+            // 首次发布时初始化
+            result.authority = ctx.accounts.authority.key();
+            result.bump = ctx.bumps.oracle_result;
+        }
+        // 只有发布者可以更新结果
+        require!(
+            result.authority == ctx.accounts.authority.key(),
+            OracleError::InvalidAuthority
+        );
+
+        // 记录轮次和获胜队伍
+        result.round = round;
+        result.winner_team = winner_team;
+        // 记录更新时间，用于判断结果是否过期
+        result.updated_at = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+}
+
+/// 发布结果指令所需的账户结构
+#[derive(Accounts)]
+#[instruction(round: Pubkey)]
+pub struct PublishResult<'info> {
+    /// 结果发布者，支付创建费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// 结果账户，使用PDA地址
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + OracleResult::LEN,
+        seeds = [b"oracle_result", round.as_ref()],
+        bump
+    )]
+    pub oracle_result: Account<'info, OracleResult>,
+
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+}
+
+/// 预言机结果账户数据结构
+#[account]
+pub struct OracleResult {
+    /// 关联的轮次
+    pub round: Pubkey,
+    /// 获胜队伍
+    pub winner_team: Pubkey,
+    /// 最近更新时间
+    pub updated_at: i64,
+    /// 结果发布者
+    pub authority: Pubkey,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl OracleResult {
+    /// 结果账户数据大小
+    pub const LEN: usize = 32 + // round
+                          32 + // winner_team
+                          8 +  // updated_at
+                          32 + // authority
+                          1;   // bump
+}
+
+/// 错误码定义
+#[error_code]
+pub enum OracleError {
+    /// 调用者不是结果发布者
+    #[msg("Signer is not the oracle authority")]
+    InvalidAuthority,
+}
@@ -1,8 +1,43 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, Mint, TokenAccount, Transfer, Burn, MintTo};
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::{self, BurnNft, CreateMasterEditionV3, CreateMetadataAccountsV3, Metadata};
+use anchor_spl::token::{spl_token::native_mint, Token};
+use anchor_spl::token_interface::{self, spl_token_2022::instruction::AuthorityType, Mint, TokenAccount, TokenInterface, TransferChecked, Burn, MintTo, CloseAccount, SetAuthority, FreezeAccount, ThawAccount};
+use mpl_token_metadata::types::DataV2;
 
 declare_id!("FL7hr4LCxSTk2p2xwbk2Qy6dnGeBxc6Daz223dzfTncz");
 
+/// 下注NFT的代币符号
+pub const BET_NFT_SYMBOL: &str = "BET";
+/// 下注NFT元数据uri的前缀，后接赛事/下注对象/金额
+pub const BET_NFT_URI_PREFIX: &str = "https://uefun.io/bet/";
+/// Metaplex元数据名称的最大字节数
+pub const BET_NFT_MAX_NAME_LEN: usize = 32;
+/// 数值市场的最大选项数量
+pub const MAX_MARKET_OUTCOMES: usize = 8;
+/// 大小盘中"大"的选项序号
+pub const OUTCOME_OVER: u8 = 0;
+/// 大小盘中"小"的选项序号
+pub const OUTCOME_UNDER: u8 = 1;
+/// 让分盘中热门方赢盘的选项序号
+pub const OUTCOME_FAVORITE: u8 = 0;
+/// 让分盘中冷门方赢盘的选项序号
+pub const OUTCOME_UNDERDOG: u8 = 1;
+/// 结果裁定人集合的最大人数
+pub const MAX_RESOLVERS: usize = 10;
+/// 下注代币白名单的最大数量
+pub const MAX_CURRENCIES: usize = 16;
+/// 基点分母
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// 手续费比例的上限(基点)
+pub const MAX_FEE_BPS: u16 = 1_000;
+/// 每份质押累计奖励的精度
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+/// 结果被推翻时罚没中奖励给争议发起人的比例(基点)
+pub const CHALLENGER_REWARD_BPS: u64 = 2_000;
+
 #[program]
 pub mod tournament_betting_system {
     use super::*;
@@ -21,6 +56,9 @@ pub mod tournament_betting_system {
         state.vote_mint = ctx.accounts.vote_mint.key();
         // 记录代币水龙头程序ID
         state.token_faucet_program_id = token_faucet_program_id;
+        // 默认任何人都可以创建赛事，与引入创建模式前的行为一致
+        state.creation_mode = CreationMode::Open;
+        state.min_creation_stake = 0;
         Ok(())
     }
 
@@ -35,6 +73,20 @@ pub mod tournament_betting_system {
         let current_timestamp = Clock::get()?.unix_timestamp;
         let tournament_bump = ctx.bumps.tournament;
         
+        // 检查下注代币: wanzi始终可用，其他代币必须在管理员维护的白名单中
+        let currency_mint = ctx.accounts.currency_mint.key();
+        if currency_mint != ctx.accounts.state.wanzi_mint {
+            let allowlist = ctx
+                .accounts
+                .currency_allowlist
+                .as_ref()
+                .ok_or(TournamentError::CurrencyNotAllowed)?;
+            require!(
+                allowlist.mints.contains(&currency_mint),
+                TournamentError::CurrencyNotAllowed
+            );
+        }
+        
         {
             // 设置赛事信息 - 放在单独作用域里避免借用冲突
             let tournament = &mut ctx.accounts.tournament;
@@ -45,26 +97,49 @@ pub mod tournament_betting_system {
             tournament.is_staked = false;
             tournament.created_at = current_timestamp;
             tournament.bump = tournament_bump;
+            // 记录赛事专属的vote代币铸造器
+            tournament.vote_mint = ctx.accounts.vote_mint.key();
+            // 记录赛事的下注代币
+            tournament.currency_mint = currency_mint;
+            // 默认不收取手续费，下注前可以修改
+            tournament.fee_bps = 0;
+            tournament.fees_locked = false;
+            tournament.reward_shares = 0;
+            tournament.acc_reward_per_share = 0;
+            // 默认由赛事管理员单独裁定结果
+            tournament.resolver_threshold = 0;
+            // 默认不设争议期，轮次完成后即可结算
+            tournament.dispute_period = 0;
+            tournament.dispute_bond = 0;
+            // 默认由管理员仲裁争议
+            tournament.governance_vote_period = 0;
+            tournament.governance_quorum = 0;
+            // 默认不使用预言机
+            tournament.oracle_program = Pubkey::default();
+            tournament.oracle_publisher = Pubkey::default();
+            tournament.oracle_max_staleness = 0;
+            // 默认不罚没质押，轮次没有裁定期限
+            tournament.slash_bps = 0;
+            tournament.resolution_period = 0;
+            // 还没有轮次，没有待了结的结果
+            tournament.unresolved_rounds = 0;
+            tournament.pending_slashes = 0;
+            tournament.dispute_window_end = 0;
         }
         
         // 如果赛事方质押了matchp
+        let mut staked = 0;
         if stake_amount > 0 {
-            // 转移matchp到合约账户
-            {
-                let cpi_accounts = Transfer {
-                    from: ctx.accounts.authority_matchp_token.to_account_info(),
-                    to: ctx.accounts.tournament_matchp_token.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
-                };
-                
-                token::transfer(
-                    CpiContext::new(
-                        ctx.accounts.token_program.to_account_info(),
-                        cpi_accounts,
-                    ),
-                    stake_amount
-                )?;
-            }
+            // 转移matchp到合约账户，按实际到账数量记录质押
+            staked = deposit_tokens(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.authority_matchp_token.to_account_info(),
+                &ctx.accounts.matchp_mint,
+                &mut ctx.accounts.tournament_matchp_token,
+                ctx.accounts.authority.to_account_info(),
+                stake_amount,
+            )?;
+            ctx.accounts.tournament.stake_amount = staked;
             
             // 铸造vote给赛事方
             {
@@ -83,13 +158,13 @@ pub mod tournament_betting_system {
                     authority: ctx.accounts.tournament.to_account_info(),
                 };
                 
-                token::mint_to(
+                token_interface::mint_to(
                     CpiContext::new_with_signer(
                         ctx.accounts.token_program.to_account_info(),
                         mint_accounts,
                         signer_seeds,
                     ),
-                    stake_amount
+                    staked
                 )?;
             }
             
@@ -97,6 +172,32 @@ pub mod tournament_betting_system {
             ctx.accounts.tournament.is_staked = true;
         }
         
+        // 按全局配置检查创建权限，合约管理员不受限制；最低质押按实际到账数量计算
+        let state = &ctx.accounts.state;
+        if state.authority != authority_key {
+            match state.creation_mode {
+                CreationMode::Open => {}
+                CreationMode::Allowlisted => require!(
+                    ctx.accounts.operator_role.is_some(),
+                    TournamentError::MissingRole
+                ),
+                CreationMode::MinStake => require!(
+                    staked >= state.min_creation_stake,
+                    TournamentError::CreationStakeTooLow
+                ),
+            }
+        }
+        
+        // 记录赛事方的质押份额
+        let stake_position = &mut ctx.accounts.stake_position;
+        stake_position.tournament = ctx.accounts.tournament.key();
+        stake_position.owner = authority_key;
+        stake_position.shares = 0;
+        stake_position.reward_debt = 0;
+        stake_position.unclaimed_rewards = 0;
+        stake_position.bump = ctx.bumps.stake_position;
+        stake_position.set_shares(&mut ctx.accounts.tournament, staked)?;
+        
         Ok(())
     }
 
@@ -107,6 +208,13 @@ pub mod tournament_betting_system {
         name: String,
         round_number: u8,
     ) -> Result<()> {
+        // 新轮次在出结果或过期之前锁定赛事的关闭和赎回
+        let tournament = &mut ctx.accounts.tournament;
+        tournament.unresolved_rounds = tournament
+            .unresolved_rounds
+            .checked_add(1)
+            .ok_or(TournamentError::Overflow)?;
+        
         let round = &mut ctx.accounts.round;
         // 关联到特定赛事
         round.tournament = ctx.accounts.tournament.key();
@@ -122,9 +230,29 @@ pub mod tournament_betting_system {
         round.created_at = Clock::get()?.unix_timestamp;
         // 记录PDA bump
         round.bump = ctx.bumps.round;
+        // 按赛事配置设置裁定截止时间，0表示没有期限
+        let resolution_period = ctx.accounts.tournament.resolution_period;
+        round.resolution_deadline = if resolution_period > 0 {
+            round
+                .created_at
+                .checked_add(resolution_period)
+                .ok_or(TournamentError::Overflow)?
+        } else {
+            0
+        };
+        // 初始化为未过期
+        round.is_expired = false;
+        // 初始化罚没状态
+        round.slash_pending = false;
+        round.is_slashed = false;
+        round.compensation_pool = 0;
         // 初始化总下注额 (新增)
-        round.total_wanzi_bets = 0;
+        round.total_currency_bets = 0;
         round.total_vote_bets = 0;
+        // 初始化结果和争议状态
+        round.winner_team = Pubkey::default();
+        round.dispute_deadline = 0;
+        round.is_disputed = false;
         Ok(())
     }
 
@@ -145,13 +273,16 @@ pub mod tournament_betting_system {
         team.is_winner = false;
         // 初始化为未淘汰状态
         team.is_eliminated = false;
+        // 初始化为未报告比分
+        team.score = 0;
+        team.is_scored = false;
         // 记录PDA bump
         team.bump = ctx.bumps.team;
         Ok(())
     }
 
     /// 下注
-    /// 用户对特定轮次的团队进行下注，根据赛事是否质押决定使用wanzi或vote代币
+    /// 用户对特定轮次的团队进行下注，根据赛事是否质押决定使用赛事下注代币或vote代币
     pub fn place_bet(
         ctx: Context<PlaceBet>,
         amount: u64,
@@ -183,6 +314,13 @@ pub mod tournament_betting_system {
         bet.is_settled = false;
         // 初始化为非获胜状态
         bet.is_winner = false;
+        // 记录代表该下注的NFT铸造器
+        bet.mint = ctx.accounts.bet_mint.key();
+        // 队伍下注不属于任何数值市场
+        bet.market = Pubkey::default();
+        bet.outcome = 0;
+        // 初始化为未领取补偿
+        bet.compensation_claimed = false;
         
         // 根据赛事是否质押决定使用哪种代币下注
         if tournament.is_staked {
@@ -192,36 +330,94 @@ pub mod tournament_betting_system {
                 TournamentError::InsufficientTokenBalance
             );
             
-            // 使用vote下注
-            let cpi_accounts = Transfer {
-                from: ctx.accounts.user_vote_token.to_account_info(),
-                to: ctx.accounts.tournament_vote_token.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            };
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-            token::transfer(cpi_ctx, amount)?;
+            // 使用vote下注，按实际到账数量记录
+            let received = deposit_tokens(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.user_vote_token.to_account_info(),
+                &ctx.accounts.vote_mint,
+                &mut ctx.accounts.tournament_vote_token,
+                ctx.accounts.user.to_account_info(),
+                amount,
+            )?;
+            bet.amount = received;
             // 累加VOTE下注总额
-            round.total_vote_bets = round.total_vote_bets.checked_add(amount).ok_or(TournamentError::Overflow)?;
+            round.total_vote_bets = round.total_vote_bets.checked_add(received).ok_or(TournamentError::Overflow)?;
+        } else if tournament.is_sol_currency() {
+            // 使用原生SOL下注，lamports转入程序拥有的托管账户
+            let sol_vault = ctx
+                .accounts
+                .sol_vault
+                .as_mut()
+                .ok_or(TournamentError::SolVaultRequired)?;
+            sol_vault.tournament = tournament.key();
+            sol_vault.bump = ctx.bumps.sol_vault.ok_or(TournamentError::SolVaultRequired)?;
+            let received = deposit_lamports(
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.user.to_account_info(),
+                sol_vault.to_account_info(),
+                amount,
+            )?;
+            bet.amount = received;
+            // 与代币下注共用下注总额
+            round.total_currency_bets = round.total_currency_bets.checked_add(received).ok_or(TournamentError::Overflow)?;
         } else {
-            // 检查用户是否有足够的wanzi代币
+            let (Some(user_currency_token), Some(currency_vault), Some(currency_mint)) = (
+                ctx.accounts.user_currency_token.as_ref(),
+                ctx.accounts.currency_vault.as_mut(),
+                ctx.accounts.currency_mint.as_ref(),
+            ) else {
+                return err!(TournamentError::CurrencyAccountsRequired);
+            };
+            // 检查用户是否有足够的下注代币
             require!(
-                ctx.accounts.user_wanzi_token.amount >= amount,
+                user_currency_token.amount >= amount,
                 TournamentError::InsufficientTokenBalance
             );
             
-            // 使用wanzi下注
-            let cpi_accounts = Transfer {
-                from: ctx.accounts.user_wanzi_token.to_account_info(),
-                to: ctx.accounts.tournament_wanzi_token.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            };
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-            token::transfer(cpi_ctx, amount)?;
-            // 累加wanzi下注总额
-            round.total_wanzi_bets = round.total_wanzi_bets.checked_add(amount).ok_or(TournamentError::Overflow)?;
+            // 使用赛事下注代币下注，按实际到账数量记录
+            let received = deposit_tokens(
+                ctx.accounts.currency_token_program.to_account_info(),
+                user_currency_token.to_account_info(),
+                currency_mint,
+                currency_vault,
+                ctx.accounts.user.to_account_info(),
+                amount,
+            )?;
+            bet.amount = received;
+            // 累加下注代币总额
+            round.total_currency_bets = round.total_currency_bets.checked_add(received).ok_or(TournamentError::Overflow)?;
+        }
+        
+        // 为该下注铸造1-of-1 NFT，持有NFT即持有下注
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        BetNft {
+            mint: ctx.accounts.bet_mint.to_account_info(),
+            token: ctx.accounts.user_bet_nft.to_account_info(),
+            metadata: ctx.accounts.bet_metadata.to_account_info(),
+            master_edition: ctx.accounts.bet_master_edition.to_account_info(),
+            tournament: tournament.to_account_info(),
+            payer: ctx.accounts.user.to_account_info(),
+            token_program: ctx.accounts.nft_token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
         }
+        .mint(
+            signer,
+            &ctx.accounts.team.name,
+            &ctx.accounts.team.key(),
+            ctx.accounts.bet.amount,
+        )?;
+        
+        // 有下注后手续费不能再修改
+        ctx.accounts.tournament.fees_locked = true;
         
         Ok(())
     }
@@ -235,6 +431,11 @@ pub mod tournament_betting_system {
         let round = &mut ctx.accounts.round;
         // 检查轮次是否激活
         require!(round.is_active, TournamentError::RoundNotActive);
+        // 配置了裁定人集合的赛事必须通过多签投票完成轮次
+        require!(
+            ctx.accounts.tournament.resolver_threshold == 0,
+            TournamentError::ResolverVoteRequired
+        );
         
         // 检查传入的获胜队伍是否属于这个轮次
         require!(
@@ -248,19 +449,50 @@ pub mod tournament_betting_system {
             TournamentError::InvalidTeam
         );
         
-        // 标记轮次为完成状态
-        round.is_active = false;
-        round.is_completed = true;
+        let current_timestamp = Clock::get()?.unix_timestamp;
         
-        // 更新获胜队伍状态
-        let winner_team = &mut ctx.accounts.winner_team;
-        winner_team.is_winner = true;
+        // 配置了预言机的赛事以预言机结果为准
+        let tournament = &ctx.accounts.tournament;
+        if tournament.oracle_program != Pubkey::default() {
+            let oracle_result = ctx
+                .accounts
+                .oracle_result
+                .as_ref()
+                .ok_or(TournamentError::OracleResultRequired)?;
+            let result = OracleResult::load(
+                oracle_result,
+                &tournament.oracle_program,
+                &tournament.oracle_publisher,
+            )?;
+            // 结果必须针对当前轮次
+            require!(result.round == round.key(), TournamentError::InvalidOracleResult);
+            // 结果不能过期
+            let age = current_timestamp
+                .checked_sub(result.updated_at)
+                .ok_or(TournamentError::Overflow)?;
+            require!(
+                age <= tournament.oracle_max_staleness,
+                TournamentError::StaleOracleResult
+            );
+            // 获胜队伍必须与预言机一致
+            require!(
+                result.winner_team == winner_team_pubkey,
+                TournamentError::InvalidOracleResult
+            );
+        }
+        
+        // 标记轮次为完成状态并更新获胜队伍，开启争议期
+        let dispute_deadline = current_timestamp
+            .checked_add(ctx.accounts.tournament.dispute_period)
+            .ok_or(TournamentError::Overflow)?;
+        round.complete(&mut ctx.accounts.winner_team, dispute_deadline);
+        ctx.accounts.tournament.record_round_result(dispute_deadline)?;
         
         Ok(())
     }
 
     /// 结算下注
-    /// 根据轮次结果结算用户下注，获胜者获得奖励
+    /// 根据轮次结果结算下注，奖励支付给下注NFT的持有者，结算后销毁NFT
     pub fn settle_bet(
         ctx: Context<SettleBet>,
     ) -> Result<()> {
@@ -272,8 +504,22 @@ pub mod tournament_betting_system {
         require!(round.is_completed, TournamentError::RoundNotCompleted);
         require!(!bet.is_settled, TournamentError::BetAlreadySettled);
         require!(bet.round == round.key(), TournamentError::BetNotInRound);
+        // 市场下注需通过settle_market_bet结算
+        require!(bet.market == Pubkey::default(), TournamentError::InvalidBetMarket);
+        // 争议期结束且没有未决争议时才能结算
+        require!(!round.is_disputed, TournamentError::RoundDisputed);
+        require!(
+            Clock::get()?.unix_timestamp >= round.dispute_deadline,
+            TournamentError::DisputeWindowOpen
+        );
         
         let is_winner = bet.team == winner_team.key() && winner_team.is_winner;
+        // VOTE赛事从赢家奖金中扣除质押奖励手续费
+        let fee = if is_winner && tournament.is_staked {
+            tournament.bet_fee(round.total_vote_bets)
+        } else {
+            0
+        };
         
         if is_winner {
             let authority_key = tournament.authority;
@@ -285,60 +531,125 @@ pub mod tournament_betting_system {
             let signer = &[&seeds[..]];
 
             if tournament.is_staked {
-                // VOTE赛事: 赢家获得所有VOTE赌注
-                let reward_amount = round.total_vote_bets;
+                // VOTE赛事: 赢家获得所有VOTE赌注，扣除质押奖励手续费
+                let reward_amount = round
+                    .total_vote_bets
+                    .checked_sub(fee)
+                    .ok_or(TournamentError::Overflow)?;
+                if fee > 0 {
+                    let rewards_vault = ctx
+                        .accounts
+                        .rewards_vault
+                        .as_ref()
+                        .ok_or(TournamentError::RewardsVaultRequired)?;
+                    transfer_tokens(
+                        ctx.accounts.token_program.to_account_info(),
+                        ctx.accounts.tournament_vote_token.to_account_info(),
+                        &ctx.accounts.vote_mint,
+                        rewards_vault.to_account_info(),
+                        tournament.to_account_info(),
+                        signer,
+                        fee,
+                    )?;
+                }
                 if reward_amount > 0 {
-                    token::transfer(
-                        CpiContext::new_with_signer(
-                            ctx.accounts.token_program.to_account_info(),
-                            Transfer {
-                                from: ctx.accounts.tournament_vote_token.to_account_info(),
-                                to: ctx.accounts.user_vote_token.to_account_info(),
-                                authority: tournament.to_account_info(),
-                            },
-                            signer,
-                        ),
+                    transfer_tokens(
+                        ctx.accounts.token_program.to_account_info(),
+                        ctx.accounts.tournament_vote_token.to_account_info(),
+                        &ctx.accounts.vote_mint,
+                        ctx.accounts.user_vote_token.to_account_info(),
+                        tournament.to_account_info(),
+                        signer,
                         reward_amount,
                     )?;
                 }
+            } else if tournament.is_sol_currency() {
+                // SOL赛事: 赢家从托管账户获得所有SOL赌注
+                let reward_amount = round.total_currency_bets;
+                if reward_amount > 0 {
+                    let sol_vault = ctx
+                        .accounts
+                        .sol_vault
+                        .as_ref()
+                        .ok_or(TournamentError::SolVaultRequired)?;
+                    pay_lamports(sol_vault, &ctx.accounts.user.to_account_info(), reward_amount)?;
+                }
             } else {
-                // WANZI赛事: 赢家获得所有WANZI赌注
-                let reward_amount = round.total_wanzi_bets;
+                // 未质押赛事: 赢家获得所有下注代币赌注
+                let reward_amount = round.total_currency_bets;
                 if reward_amount > 0 {
-                    token::transfer(
-                        CpiContext::new_with_signer(
-                            ctx.accounts.token_program.to_account_info(),
-                            Transfer {
-                                from: ctx.accounts.tournament_wanzi_token.to_account_info(),
-                                to: ctx.accounts.user_wanzi_token.to_account_info(),
-                                authority: tournament.to_account_info(),
-                            },
-                            signer,
-                        ),
+                    let (Some(user_currency_token), Some(currency_vault), Some(currency_mint)) = (
+                        ctx.accounts.user_currency_token.as_ref(),
+                        ctx.accounts.currency_vault.as_ref(),
+                        ctx.accounts.currency_mint.as_ref(),
+                    ) else {
+                        return err!(TournamentError::CurrencyAccountsRequired);
+                    };
+                    transfer_tokens(
+                        ctx.accounts.currency_token_program.to_account_info(),
+                        currency_vault.to_account_info(),
+                        currency_mint,
+                        user_currency_token.to_account_info(),
+                        tournament.to_account_info(),
+                        signer,
                         reward_amount,
                     )?;
                 }
             }
         }
         
+        // 销毁下注NFT，租金返还给持有者
+        metadata::burn_nft(
+            CpiContext::new(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                BurnNft {
+                    metadata: ctx.accounts.bet_metadata.to_account_info(),
+                    owner: ctx.accounts.user.to_account_info(),
+                    mint: ctx.accounts.bet_mint.to_account_info(),
+                    token: ctx.accounts.user_bet_nft.to_account_info(),
+                    edition: ctx.accounts.bet_master_edition.to_account_info(),
+                    spl_token: ctx.accounts.nft_token_program.to_account_info(),
+                },
+            ),
+            None,
+        )?;
+        
+        bet.user = ctx.accounts.user.key();
         bet.is_settled = true;
         bet.is_winner = is_winner;
         
+        // 手续费按份额累计给质押者
+        if fee > 0 {
+            ctx.accounts.tournament.distribute_rewards(fee)?;
+        }
+        
         Ok(())
     }
 
     /// 关闭赛事
     /// 结束赛事，处理质押的代币和奖励
+    /// 所有轮次出结果或过期、争议期结束、争议已裁决且罚没已执行后才能关闭
     pub fn close_tournament(ctx: Context<CloseTournament>) -> Result<()> {
         // 先获取所有必要的值，避免同时可变和不可变借用
         let is_active = ctx.accounts.tournament.is_active;
         let is_staked = ctx.accounts.tournament.is_staked;
-        let stake_amount = ctx.accounts.tournament.stake_amount;
+        // 只返还赛事方份额的价值，委托者的matchp由其自行赎回
+        let organizer_value = ctx
+            .accounts
+            .tournament
+            .stake_value(ctx.accounts.stake_position.shares);
         let bump = ctx.accounts.tournament.bump;
         let authority_key = ctx.accounts.authority.key();
 
         // 检查赛事是否激活
         require!(is_active, TournamentError::TournamentNotActive);
+        // 轮次结果全部了结后才能关闭，避免逃避罚没或转走退款和补偿所需的资金
+        require!(
+            !ctx.accounts
+                .tournament
+                .has_unsettled_rounds(Clock::get()?.unix_timestamp),
+            TournamentError::UnsettledRounds
+        );
         
         // 更新赛事状态为关闭
         ctx.accounts.tournament.is_active = false;
@@ -364,22 +675,31 @@ pub mod tournament_betting_system {
                 burn_accounts,
                 signer,
             );
-            token::burn(cpi_ctx, ctx.accounts.tournament_vote_token.amount)?;
+            token_interface::burn(cpi_ctx, ctx.accounts.tournament_vote_token.amount)?;
 
             // 返还matchp到管理员账户
-            let transfer_accounts = Transfer {
-                from: ctx.accounts.tournament_matchp_token.to_account_info(),
-                to: ctx.accounts.authority_matchp_token.to_account_info(),
-                authority: ctx.accounts.tournament.to_account_info(),
-            };
-            let cpi_ctx = CpiContext::new_with_signer(
+            transfer_tokens(
                 ctx.accounts.token_program.to_account_info(),
-                transfer_accounts,
+                ctx.accounts.tournament_matchp_token.to_account_info(),
+                &ctx.accounts.matchp_mint,
+                ctx.accounts.authority_matchp_token.to_account_info(),
+                ctx.accounts.tournament.to_account_info(),
                 signer,
-            );
-            token::transfer(cpi_ctx, stake_amount)?;
+                organizer_value,
+            )?;
+            
+            // 清空赛事方的质押份额
+            ctx.accounts.tournament.stake_amount = ctx
+                .accounts
+                .tournament
+                .stake_amount
+                .checked_sub(organizer_value)
+                .ok_or(TournamentError::Overflow)?;
+            ctx.accounts
+                .stake_position
+                .set_shares(&mut ctx.accounts.tournament, 0)?;
         } else {
-            // 如果使用wanzi下注，返还到管理员地址
+            // 如果使用下注代币下注，返还到管理员地址
             let authority_seeds = authority_key.as_ref();
             let seeds = &[
                 b"tournament".as_ref(),
@@ -388,19 +708,53 @@ pub mod tournament_betting_system {
             ];
             let signer = &[&seeds[..]];
 
-            // 返还wanzi到管理员地址
-            let transfer_accounts = Transfer {
-                from: ctx.accounts.tournament_wanzi_token.to_account_info(),
-                to: ctx.accounts.authority_wanzi_token.to_account_info(),
-                authority: ctx.accounts.tournament.to_account_info(),
-            };
-            let cpi_ctx = CpiContext::new_with_signer(
+            if ctx.accounts.tournament.is_sol_currency() {
+                // 返还托管账户中租金之外的SOL到管理员地址
+                if let Some(sol_vault) = ctx.accounts.sol_vault.as_ref() {
+                    let rent_reserve = Rent::get()?.minimum_balance(8 + SolVault::LEN);
+                    let pool = sol_vault.get_lamports().saturating_sub(rent_reserve);
+                    pay_lamports(sol_vault, &ctx.accounts.authority.to_account_info(), pool)?;
+                }
+            } else {
+                let (Some(authority_currency_token), Some(currency_vault), Some(currency_mint)) = (
+                    ctx.accounts.authority_currency_token.as_ref(),
+                    ctx.accounts.currency_vault.as_ref(),
+                    ctx.accounts.currency_mint.as_ref(),
+                ) else {
+                    return err!(TournamentError::CurrencyAccountsRequired);
+                };
+                // 返还下注代币到管理员地址
+                transfer_tokens(
+                    ctx.accounts.currency_token_program.to_account_info(),
+                    currency_vault.to_account_info(),
+                    currency_mint,
+                    authority_currency_token.to_account_info(),
+                    ctx.accounts.tournament.to_account_info(),
+                    signer,
+                    currency_vault.amount,
+                )?;
+            }
+        }
+        
+        // 撤销vote代币的铸币权限，赛事关闭后不能再铸造该赛事的vote
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[bump],
+        ];
+        let signer = &[&seeds[..]];
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                transfer_accounts,
+                SetAuthority {
+                    current_authority: ctx.accounts.tournament.to_account_info(),
+                    account_or_mint: ctx.accounts.vote_mint.to_account_info(),
+                },
                 signer,
-            );
-            token::transfer(cpi_ctx, ctx.accounts.tournament_wanzi_token.amount)?;
-        }
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
         
         Ok(())
     }
@@ -416,31 +770,186 @@ pub mod tournament_betting_system {
         Ok(())
     }
 
+    /// 提议转移合约管理员
+    /// 新管理员需要调用accept_authority确认后才会生效，再次提议会覆盖之前的提议
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        require!(new_authority != Pubkey::default(), TournamentError::InvalidPendingAuthority);
+        let state = &mut ctx.accounts.state;
+        state.pending_authority = new_authority;
+
+        emit!(AuthorityProposed {
+            authority: state.authority,
+            pending_authority: new_authority,
+        });
+        Ok(())
+    }
+
+    /// 接受合约管理员转移
+    /// 由被提议的新管理员签名，确认后原管理员失去权限
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        let previous_authority = state.authority;
+        state.authority = ctx.accounts.pending_authority.key();
+        state.pending_authority = Pubkey::default();
+
+        emit!(AuthorityAccepted {
+            previous_authority,
+            authority: state.authority,
+        });
+        Ok(())
+    }
+
+    /// 取消合约管理员转移
+    /// 当前管理员撤回尚未被接受的提议
+    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        require!(
+            state.pending_authority != Pubkey::default(),
+            TournamentError::NoPendingAuthority
+        );
+        let pending_authority = state.pending_authority;
+        state.pending_authority = Pubkey::default();
+
+        emit!(AuthorityTransferCancelled {
+            authority: state.authority,
+            pending_authority,
+        });
+        Ok(())
+    }
+
+    /// 设置全局暂停
+    /// 合约管理员或暂停管理员调用，暂停期间禁止下注、质押和创建赛事，退款和领取不受影响
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.state.paused = paused;
+
+        emit!(PauseUpdated {
+            tournament: None,
+            paused,
+            pauser: ctx.accounts.pauser.key(),
+        });
+        Ok(())
+    }
+
+    /// 设置赛事暂停
+    /// 合约管理员或暂停管理员调用，只影响单个赛事
+    pub fn set_tournament_paused(ctx: Context<SetTournamentPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.tournament.paused = paused;
+
+        emit!(PauseUpdated {
+            tournament: Some(ctx.accounts.tournament.key()),
+            paused,
+            pauser: ctx.accounts.pauser.key(),
+        });
+        Ok(())
+    }
+
+    /// 配置赛事创建权限
+    /// 合约管理员或全局管理员选择开放创建、仅限赛事运营，或要求创建时质押最低数量的matchp
+    pub fn configure_tournament_creation(
+        ctx: Context<ConfigureTournamentCreation>,
+        creation_mode: CreationMode,
+        min_creation_stake: u64,
+    ) -> Result<()> {
+        if creation_mode == CreationMode::MinStake {
+            require!(min_creation_stake > 0, TournamentError::InvalidStakeAmount);
+        }
+        let state = &mut ctx.accounts.state;
+        state.creation_mode = creation_mode;
+        state.min_creation_stake = min_creation_stake;
+
+        emit!(TournamentCreationConfigured {
+            creation_mode,
+            min_creation_stake,
+        });
+        Ok(())
+    }
+
+    /// 授予角色
+    /// 合约管理员或全局管理员为账户授予角色，角色账户存在即代表持有该角色
+    /// 全局管理员角色只能由合约管理员授予，防止全局管理员自我复制
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, holder: Pubkey) -> Result<()> {
+        require!(
+            role != Role::Admin || ctx.accounts.state.authority == ctx.accounts.authority.key(),
+            TournamentError::AdminRoleRestricted
+        );
+        
+        let role_assignment = &mut ctx.accounts.role_assignment;
+        role_assignment.role = role;
+        role_assignment.holder = holder;
+        role_assignment.bump = ctx.bumps.role_assignment;
+
+        emit!(RoleGranted {
+            role,
+            holder,
+            granted_by: ctx.accounts.authority.key(),
+        });
+        Ok(())
+    }
+
+    /// 撤销角色
+    /// 关闭角色账户，租金退还给调用者
+    /// 全局管理员角色只能由合约管理员撤销
+    pub fn revoke_role(ctx: Context<RevokeRole>, role: Role, holder: Pubkey) -> Result<()> {
+        require!(
+            role != Role::Admin || ctx.accounts.state.authority == ctx.accounts.authority.key(),
+            TournamentError::AdminRoleRestricted
+        );
+        
+        emit!(RoleRevoked {
+            role,
+            holder,
+            revoked_by: ctx.accounts.authority.key(),
+        });
+        Ok(())
+    }
+
+    /// 添加下注代币
+    /// 管理员将代币加入白名单，赛事方创建赛事时可以选择该代币作为下注代币
+    pub fn add_currency(ctx: Context<AddCurrency>, mint: Pubkey) -> Result<()> {
+        let allowlist = &mut ctx.accounts.currency_allowlist;
+        // 检查是否已在白名单中
+        require!(!allowlist.mints.contains(&mint), TournamentError::CurrencyAlreadyAllowed);
+        // 检查白名单容量
+        require!(allowlist.mints.len() < MAX_CURRENCIES, TournamentError::CurrencyAllowlistFull);
+        
+        allowlist.mints.push(mint);
+        allowlist.bump = ctx.bumps.currency_allowlist;
+        Ok(())
+    }
+
+    /// 移除下注代币
+    /// 管理员将代币移出白名单，已使用该代币的赛事不受影响
+    pub fn remove_currency(ctx: Context<RemoveCurrency>, mint: Pubkey) -> Result<()> {
+        let allowlist = &mut ctx.accounts.currency_allowlist;
+        let index = allowlist
+            .mints
+            .iter()
+            .position(|allowed| *allowed == mint)
+            .ok_or(TournamentError::CurrencyNotAllowed)?;
+        allowlist.mints.remove(index);
+        Ok(())
+    }
+
     /// 对已创建的赛事进行质押
-    /// 转移matchp代币到合约账户，并铸造相应的vote代币
+    /// 转移matchp代币到合约账户，并铸造相应的vote代币；已质押的赛事可以追加质押
     pub fn stake_tournament(
         ctx: Context<StakeTournament>,
         amount: u64,
     ) -> Result<()> {
         // 检查赛事是否激活
         require!(ctx.accounts.tournament.is_active, TournamentError::TournamentNotActive);
-        // 检查赛事是否已经质押
-        require!(!ctx.accounts.tournament.is_staked, TournamentError::TournamentAlreadyStaked);
         
         // 检查质押金额
         require!(amount > 0, TournamentError::InvalidStakeAmount);
         
-        // 转移matchp到合约账户
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.authority_matchp_token.to_account_info(),
-                    to: ctx.accounts.tournament_matchp_token.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
-                },
-            ),
-            amount
+        // 转移matchp到合约账户，按实际到账数量铸造vote和计算份额
+        let received = deposit_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.authority_matchp_token.to_account_info(),
+            &ctx.accounts.matchp_mint,
+            &mut ctx.accounts.tournament_matchp_token,
+            ctx.accounts.authority.to_account_info(),
+            amount,
         )?;
         
         // 使用tournament PDA作为签名者铸造代币
@@ -453,7 +962,7 @@ pub mod tournament_betting_system {
         let signer_seeds = &[&seeds[..]];
         
         // 铸造vote给赛事方
-        token::mint_to(
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 MintTo {
@@ -463,451 +972,5556 @@ pub mod tournament_betting_system {
                 },
                 signer_seeds,
             ),
-            amount
+            received
         )?;
         
-        // 更新赛事状态
+        // 按当前每份价值计算新增份额
         let tournament = &mut ctx.accounts.tournament;
-        tournament.stake_amount = amount;
+        let new_shares = tournament.shares_for_deposit(received)?;
+        
+        // 更新赛事状态，累加质押金额
+        tournament.stake_amount = tournament
+            .stake_amount
+            .checked_add(received)
+            .ok_or(TournamentError::Overflow)?;
         tournament.is_staked = true;
         
+        // 增加赛事方的质押份额
+        let stake_position = &mut ctx.accounts.stake_position;
+        let shares = stake_position
+            .shares
+            .checked_add(new_shares)
+            .ok_or(TournamentError::Overflow)?;
+        stake_position.set_shares(tournament, shares)?;
+        
         Ok(())
     }
-}
 
-/// 初始化指令所需的账户结构
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    /// 合约管理员，支付初始化费用
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    /// 状态账户，使用PDA地址
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + State::LEN,
-        seeds = [b"state"],
-        bump
-    )]
-    pub state: Account<'info, State>,
-    
-    /// wanzi代币铸造器
-    pub wanzi_mint: Box<Account<'info, Mint>>,
-    /// matchp代币铸造器
-    pub matchp_mint: Box<Account<'info, Mint>>,
-    /// vote代币铸造器
-    pub vote_mint: Box<Account<'info, Mint>>,
-    
-    /// 系统程序
-    pub system_program: Program<'info, System>,
-    /// 租金系统变量
-    pub rent: Sysvar<'info, Rent>,
-}
+    /// 赎回部分质押
+    /// 赛事方销毁自己持有的vote代币，从自己的份额中取回等量的matchp
+    /// 赎回后vote的总供应量(包括下注中的vote)不能超过赛事方剩余份额的价值，保证所有vote都有matchp支撑
+    /// 最低质押模式下，赛事进行中赎回后的质押不能低于创建赛事的最低质押
+    /// 赛事有未出结果的轮次、未结束的争议期、未裁决的争议或待执行的罚没时不能赎回
+    pub fn unstake_tournament(
+        ctx: Context<UnstakeTournament>,
+        amount: u64,
+    ) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let position_shares = ctx.accounts.stake_position.shares;
+        
+        // 检查赛事是否激活且已质押
+        require!(tournament.is_active, TournamentError::TournamentNotActive);
+        require!(tournament.is_staked, TournamentError::TournamentNotStaked);
+        // 轮次结果了结之前质押不能赎回，避免逃避罚没
+        require!(
+            !tournament.has_unsettled_rounds(Clock::get()?.unix_timestamp),
+            TournamentError::UnsettledRounds
+        );
+        // 只能赎回赛事方自己份额的价值，委托者的matchp不能被赎回
+        require!(
+            amount > 0 && amount <= tournament.stake_value(position_shares),
+            TournamentError::InvalidStakeAmount
+        );
+        let burned_shares = tournament.shares_for_withdrawal(amount)?.min(position_shares);
+        
+        // 赎回后剩余的vote供应量必须仍由赛事方剩余份额支撑
+        let remaining_supply = ctx
+            .accounts
+            .vote_mint
+            .supply
+            .checked_sub(amount)
+            .ok_or(TournamentError::InsufficientTokenBalance)?;
+        let remaining_stake = tournament
+            .stake_amount
+            .checked_sub(amount)
+            .ok_or(TournamentError::Overflow)?;
+        let remaining_total_shares = tournament
+            .reward_shares
+            .checked_sub(burned_shares)
+            .ok_or(TournamentError::Overflow)?;
+        let remaining_shares = position_shares
+            .checked_sub(burned_shares)
+            .ok_or(TournamentError::Overflow)?;
+        let remaining_value = if remaining_total_shares == 0 {
+            0
+        } else {
+            (remaining_shares as u128 * remaining_stake as u128
+                / remaining_total_shares as u128) as u64
+        };
+        require!(
+            remaining_supply <= remaining_value,
+            TournamentError::UnbackedVoteSupply
+        );
+        // 最低质押模式下赛事进行中不能赎回到最低质押以下，合约管理员不受限制
+        let state = &ctx.accounts.state;
+        if state.creation_mode == CreationMode::MinStake && state.authority != tournament.authority {
+            require!(
+                remaining_stake >= state.min_creation_stake,
+                TournamentError::CreationStakeTooLow
+            );
+        }
+        
+        // 销毁赛事方持有的vote
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.vote_mint.to_account_info(),
+                    from: ctx.accounts.authority_vote_token.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // 返还等量的matchp
+        transfer_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.tournament_matchp_token.to_account_info(),
+            &ctx.accounts.matchp_mint,
+            ctx.accounts.authority_matchp_token.to_account_info(),
+            tournament.to_account_info(),
+            signer,
+            amount,
+        )?;
+        
+        ctx.accounts.tournament.stake_amount = remaining_stake;
+        
+        // 减少赛事方的质押份额
+        ctx.accounts
+            .stake_position
+            .set_shares(&mut ctx.accounts.tournament, remaining_shares)?;
+        
+        Ok(())
+    }
 
-/// 创建赛事指令所需的账户结构
-#[derive(Accounts)]
-pub struct CreateTournament<'info> {
-    /// 赛事管理员，支付创建费用
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    /// 赛事账户，使用PDA地址
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + Tournament::LEN,
-        seeds = [b"tournament", authority.key().as_ref()],
-        bump
-    )]
-    pub tournament: Account<'info, Tournament>,
-    
-    /// 管理员的matchp代币账户
-    #[account(mut)]
-    pub authority_matchp_token: Box<Account<'info, TokenAccount>>,
-    
-    /// 管理员的vote代币账户
-    #[account(mut)]
-    pub authority_vote_token: Box<Account<'info, TokenAccount>>,
-    
-    /// 赛事的matchp代币账户
-    #[account(
-        init,
-        payer = authority,
-        token::mint = matchp_mint,
-        token::authority = tournament,
-        seeds = [b"tournament_matchp", tournament.key().as_ref()],
-        bump
-    )]
-    pub tournament_matchp_token: Box<Account<'info, TokenAccount>>,
-    
-    /// 赛事的vote代币账户
-    #[account(
-        init,
-        payer = authority,
-        token::mint = vote_mint,
-        token::authority = tournament,
-        seeds = [b"tournament_vote", tournament.key().as_ref()],
-        bump
-    )]
-    pub tournament_vote_token: Box<Account<'info, TokenAccount>>,
-    
-    /// matchp代币铸造器
-    pub matchp_mint: Box<Account<'info, Mint>>,
+    /// 委托质押
+    /// 玩家将matchp加入赛事的质押池，按当前每份价值获得份额和等量的质押凭证
+    /// 委托者按份额分享手续费奖励，也按份额承担罚没损失
+    /// 质押凭证账户由赛事冻结，凭证不能转让，只能由委托者本人赎回
+    pub fn delegate_stake(ctx: Context<DelegateStake>, amount: u64) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        
+        // 只能委托给已质押的赛事
+        require!(tournament.is_staked, TournamentError::TournamentNotStaked);
+        require!(amount > 0, TournamentError::InvalidStakeAmount);
+        
+        // 转入委托的matchp，按实际到账数量计算份额
+        let received = deposit_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.delegator_matchp_token.to_account_info(),
+            &ctx.accounts.matchp_mint,
+            &mut ctx.accounts.tournament_matchp_token,
+            ctx.accounts.delegator.to_account_info(),
+            amount,
+        )?;
+        let new_shares = tournament.shares_for_deposit(received)?;
+        require!(new_shares > 0, TournamentError::InvalidStakeAmount);
+        
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // 再次委托时先解冻质押凭证账户
+        if ctx.accounts.delegator_receipt_token.is_frozen() {
+            token_interface::thaw_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                ThawAccount {
+                    account: ctx.accounts.delegator_receipt_token.to_account_info(),
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    authority: tournament.to_account_info(),
+                },
+                signer,
+            ))?;
+        }
+        
+        // 铸造与份额等量的质押凭证
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    to: ctx.accounts.delegator_receipt_token.to_account_info(),
+                    authority: tournament.to_account_info(),
+                },
+                signer,
+            ),
+            new_shares,
+        )?;
+        
+        // 冻结质押凭证账户，份额记在委托者名下，凭证不能转让
+        token_interface::freeze_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            FreezeAccount {
+                account: ctx.accounts.delegator_receipt_token.to_account_info(),
+                mint: ctx.accounts.receipt_mint.to_account_info(),
+                authority: tournament.to_account_info(),
+            },
+            signer,
+        ))?;
+        
+        let stake_position = &mut ctx.accounts.stake_position;
+        if stake_position.owner == Pubkey::default() {
+            // 首次委托时初始化
+            stake_position.tournament = ctx.accounts.tournament.key();
+            stake_position.owner = ctx.accounts.delegator.key();
+            stake_position.bump = ctx.bumps.stake_position;
+        }
+        
+        let tournament = &mut ctx.accounts.tournament;
+        tournament.stake_amount = tournament
+            .stake_amount
+            .checked_add(received)
+            .ok_or(TournamentError::Overflow)?;
+        let shares = stake_position
+            .shares
+            .checked_add(new_shares)
+            .ok_or(TournamentError::Overflow)?;
+        stake_position.set_shares(tournament, shares)?;
+        
+        Ok(())
+    }
+
+    /// 取消委托
+    /// 委托者销毁质押凭证，按当前每份价值取回matchp；赛事关闭后仍可取回
+    pub fn undelegate_stake(ctx: Context<UndelegateStake>, shares: u64) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let position_shares = ctx.accounts.stake_position.shares;
+        
+        // 检查赎回份额
+        require!(
+            shares > 0 && shares <= position_shares,
+            TournamentError::InvalidStakeAmount
+        );
+        // 按当前每份价值计算可取回的matchp，已被罚没的部分按份额扣除
+        let amount = tournament.stake_value(shares);
+        
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // 解冻质押凭证账户后销毁凭证，剩余凭证重新冻结
+        if ctx.accounts.delegator_receipt_token.is_frozen() {
+            token_interface::thaw_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                ThawAccount {
+                    account: ctx.accounts.delegator_receipt_token.to_account_info(),
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    authority: tournament.to_account_info(),
+                },
+                signer,
+            ))?;
+        }
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    from: ctx.accounts.delegator_receipt_token.to_account_info(),
+                    authority: ctx.accounts.delegator.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+        token_interface::freeze_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            FreezeAccount {
+                account: ctx.accounts.delegator_receipt_token.to_account_info(),
+                mint: ctx.accounts.receipt_mint.to_account_info(),
+                authority: tournament.to_account_info(),
+            },
+            signer,
+        ))?;
+        
+        if amount > 0 {
+            transfer_tokens(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.tournament_matchp_token.to_account_info(),
+                &ctx.accounts.matchp_mint,
+                ctx.accounts.delegator_matchp_token.to_account_info(),
+                tournament.to_account_info(),
+                signer,
+                amount,
+            )?;
+        }
+        
+        let tournament = &mut ctx.accounts.tournament;
+        tournament.stake_amount = tournament
+            .stake_amount
+            .checked_sub(amount)
+            .ok_or(TournamentError::Overflow)?;
+        let remaining_shares = position_shares
+            .checked_sub(shares)
+            .ok_or(TournamentError::Overflow)?;
+        ctx.accounts
+            .stake_position
+            .set_shares(tournament, remaining_shares)?;
+        
+        Ok(())
+    }
+
+    /// 配置手续费
+    /// 赛事管理员设置从获胜奖金中抽取的手续费比例(基点)，手续费进入奖励池按质押份额分配给质押者
+    /// 手续费不能超过MAX_FEE_BPS，赛事有下注或轮次出结果后不能再修改
+    pub fn configure_fees(ctx: Context<ConfigureFees>, fee_bps: u16) -> Result<()> {
+        // 检查手续费比例
+        require!(fee_bps <= MAX_FEE_BPS, TournamentError::InvalidFeeConfig);
+        // 有下注或轮次出结果后不能修改，避免赛事方在结果已知后抽走奖金
+        require!(!ctx.accounts.tournament.fees_locked, TournamentError::FeesLocked);
+        
+        ctx.accounts.tournament.fee_bps = fee_bps;
+        
+        Ok(())
+    }
+
+    /// 领取质押奖励
+    /// 质押者领取按份额和质押时长累计的手续费奖励
+    pub fn claim_stake_rewards(ctx: Context<ClaimStakeRewards>) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let stake_position = &mut ctx.accounts.stake_position;
+        
+        // 结算截至当前的奖励
+        stake_position.harvest(tournament)?;
+        let amount = stake_position.unclaimed_rewards;
+        require!(amount > 0, TournamentError::NoRewards);
+        stake_position.unclaimed_rewards = 0;
+        
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        transfer_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.rewards_vault.to_account_info(),
+            &ctx.accounts.vote_mint,
+            ctx.accounts.owner_vote_token.to_account_info(),
+            tournament.to_account_info(),
+            signer,
+            amount,
+        )?;
+        
+        Ok(())
+    }
+
+    /// 配置vote分发
+    /// 质押赛事的管理员设置wanzi兑换vote的比例(基点)和每位注册玩家可领取的vote数量
+    pub fn configure_vote_distribution(
+        ctx: Context<ConfigureVoteDistribution>,
+        swap_rate_bps: u64,
+        player_allowance: u64,
+    ) -> Result<()> {
+        // 只有质押赛事使用vote下注
+        require!(ctx.accounts.tournament.is_staked, TournamentError::TournamentNotStaked);
+        
+        let distribution = &mut ctx.accounts.vote_distribution;
+        if distribution.tournament == Pubkey::default() {
+            // 首次配置时初始化
+            distribution.tournament = ctx.accounts.tournament.key();
+            distribution.total_swapped = 0;
+            distribution.total_claimed = 0;
+            distribution.bump = ctx.bumps.vote_distribution;
+        }
+        // 设置兑换比例，0表示关闭兑换
+        distribution.swap_rate_bps = swap_rate_bps;
+        // 设置注册玩家的领取额度，0表示关闭领取
+        distribution.player_allowance = player_allowance;
+        
+        Ok(())
+    }
+
+    /// 注入vote分发资金
+    /// 赛事管理员将自己持有的vote转入分发托管账户，供玩家兑换和领取
+    pub fn fund_vote_distribution(
+        ctx: Context<FundVoteDistribution>,
+        amount: u64,
+    ) -> Result<()> {
+        // 检查注入数量
+        require!(amount > 0, TournamentError::InvalidVoteAmount);
+        
+        transfer_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.authority_vote_token.to_account_info(),
+            &ctx.accounts.vote_mint,
+            ctx.accounts.distribution_vault.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            &[],
+            amount,
+        )?;
+        
+        Ok(())
+    }
+
+    /// 注册玩家
+    /// 赛事管理员登记玩家，每位注册玩家可领取一次vote额度
+    pub fn register_player(ctx: Context<RegisterPlayer>, player: Pubkey) -> Result<()> {
+        let registration = &mut ctx.accounts.player_registration;
+        // 关联到特定赛事
+        registration.tournament = ctx.accounts.tournament.key();
+        // 记录玩家
+        registration.player = player;
+        // 初始化为未领取状态
+        registration.has_claimed = false;
+        // 记录注册时间
+        registration.registered_at = Clock::get()?.unix_timestamp;
+        // 记录PDA bump
+        registration.bump = ctx.bumps.player_registration;
+        
+        Ok(())
+    }
+
+    /// 领取vote额度
+    /// 注册玩家从分发托管账户领取固定数量的vote，每位玩家只能领取一次
+    pub fn claim_vote_allowance(ctx: Context<ClaimVoteAllowance>) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let allowance = ctx.accounts.vote_distribution.player_allowance;
+        
+        // 检查是否开启领取
+        require!(allowance > 0, TournamentError::VoteDistributionDisabled);
+        // 每位玩家只能领取一次
+        require!(
+            !ctx.accounts.player_registration.has_claimed,
+            TournamentError::AllowanceAlreadyClaimed
+        );
+        // 检查托管账户余额
+        require!(
+            ctx.accounts.distribution_vault.amount >= allowance,
+            TournamentError::InsufficientTokenBalance
+        );
+        
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        transfer_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.distribution_vault.to_account_info(),
+            &ctx.accounts.vote_mint,
+            ctx.accounts.player_vote_token.to_account_info(),
+            tournament.to_account_info(),
+            signer,
+            allowance,
+        )?;
+        
+        ctx.accounts.player_registration.has_claimed = true;
+        let distribution = &mut ctx.accounts.vote_distribution;
+        distribution.total_claimed = distribution
+            .total_claimed
+            .checked_add(allowance)
+            .ok_or(TournamentError::Overflow)?;
+        
+        Ok(())
+    }
+
+    /// 用wanzi兑换vote
+    /// 玩家按赛事管理员设置的比例支付wanzi给赛事管理员，从分发托管账户获得vote
+    pub fn swap_wanzi_for_vote(
+        ctx: Context<SwapWanziForVote>,
+        wanzi_amount: u64,
+    ) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let swap_rate_bps = ctx.accounts.vote_distribution.swap_rate_bps;
+        
+        // 检查是否开启兑换
+        require!(swap_rate_bps > 0, TournamentError::VoteDistributionDisabled);
+        // 检查兑换数量
+        require!(wanzi_amount > 0, TournamentError::InvalidVoteAmount);
+        
+        let vote_amount = u64::try_from(
+            wanzi_amount as u128 * swap_rate_bps as u128 / BPS_DENOMINATOR as u128,
+        )
+        .map_err(|_| TournamentError::Overflow)?;
+        require!(vote_amount > 0, TournamentError::InvalidVoteAmount);
+        // 检查托管账户余额
+        require!(
+            ctx.accounts.distribution_vault.amount >= vote_amount,
+            TournamentError::InsufficientTokenBalance
+        );
+        
+        // 玩家支付wanzi给赛事管理员
+        transfer_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.player_wanzi_token.to_account_info(),
+            &ctx.accounts.wanzi_mint,
+            ctx.accounts.authority_wanzi_token.to_account_info(),
+            ctx.accounts.player.to_account_info(),
+            &[],
+            wanzi_amount,
+        )?;
+        
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // 从托管账户发放vote
+        transfer_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.distribution_vault.to_account_info(),
+            &ctx.accounts.vote_mint,
+            ctx.accounts.player_vote_token.to_account_info(),
+            tournament.to_account_info(),
+            signer,
+            vote_amount,
+        )?;
+        
+        let distribution = &mut ctx.accounts.vote_distribution;
+        distribution.total_swapped = distribution
+            .total_swapped
+            .checked_add(vote_amount)
+            .ok_or(TournamentError::Overflow)?;
+        
+        Ok(())
+    }
+
+    /// 转让下注
+    /// 当前持有者将未结算的下注及其NFT转让给其他钱包，结算时奖励支付给新的持有者
+    pub fn transfer_bet(ctx: Context<TransferBet>) -> Result<()> {
+        let new_owner = ctx.accounts.new_owner.key();
+        // 检查下注是否已结算
+        require!(!ctx.accounts.bet.is_settled, TournamentError::BetAlreadySettled);
+        // 不能转让给自己
+        require!(new_owner != ctx.accounts.user.key(), TournamentError::InvalidBetRecipient);
+        
+        // 转移下注NFT到新持有者
+        transfer_tokens(
+            ctx.accounts.nft_token_program.to_account_info(),
+            ctx.accounts.user_bet_nft.to_account_info(),
+            &ctx.accounts.bet_mint,
+            ctx.accounts.new_owner_bet_nft.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            &[],
+            1,
+        )?;
+        
+        // 更新下注持有者
+        ctx.accounts.bet.user = new_owner;
+        
+        Ok(())
+    }
+
+    /// 挂单出售下注
+    /// 持有者以wanzi标价出售未结算的下注，下注NFT托管到挂单账户直到成交或撤单
+    pub fn list_bet(
+        ctx: Context<ListBet>,
+        price: u64,
+    ) -> Result<()> {
+        // 检查标价
+        require!(price > 0, TournamentError::InvalidListingPrice);
+        // 只有轮次未出结果且未过期时才允许交易
+        require!(
+            !ctx.accounts.round.is_completed && !ctx.accounts.round.is_expired,
+            TournamentError::RoundClosedForTrading
+        );
+        
+        // 将下注NFT转入托管账户
+        transfer_tokens(
+            ctx.accounts.nft_token_program.to_account_info(),
+            ctx.accounts.seller_bet_nft.to_account_info(),
+            &ctx.accounts.bet_mint,
+            ctx.accounts.escrow_bet_nft.to_account_info(),
+            ctx.accounts.seller.to_account_info(),
+            &[],
+            1,
+        )?;
+        
+        let listing = &mut ctx.accounts.listing;
+        // 关联到特定下注
+        listing.bet = ctx.accounts.bet.key();
+        // 记录卖家
+        listing.seller = ctx.accounts.seller.key();
+        // 记录wanzi标价
+        listing.price = price;
+        // 记录挂单时间
+        listing.created_at = Clock::get()?.unix_timestamp;
+        // 记录PDA bump
+        listing.bump = ctx.bumps.listing;
+        
+        Ok(())
+    }
+
+    /// 撤销挂单
+    /// 卖家取回托管的下注NFT并关闭挂单
+    pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
+        let bet_key = ctx.accounts.bet.key();
+        let seeds = &[
+            b"listing".as_ref(),
+            bet_key.as_ref(),
+            &[ctx.accounts.listing.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // 将下注NFT退还给卖家
+        transfer_tokens(
+            ctx.accounts.nft_token_program.to_account_info(),
+            ctx.accounts.escrow_bet_nft.to_account_info(),
+            &ctx.accounts.bet_mint,
+            ctx.accounts.seller_bet_nft.to_account_info(),
+            ctx.accounts.listing.to_account_info(),
+            signer,
+            1,
+        )?;
+        
+        // 关闭托管账户，租金返还给卖家
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.nft_token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_bet_nft.to_account_info(),
+                destination: ctx.accounts.seller.to_account_info(),
+                authority: ctx.accounts.listing.to_account_info(),
+            },
+            signer,
+        ))?;
+        
+        Ok(())
+    }
+
+    /// 购买挂单的下注
+    /// 买家支付wanzi给卖家，同时原子地获得托管的下注NFT
+    pub fn buy_bet(ctx: Context<BuyBet>) -> Result<()> {
+        // 只有轮次未出结果且未过期时才允许交易
+        require!(
+            !ctx.accounts.round.is_completed && !ctx.accounts.round.is_expired,
+            TournamentError::RoundClosedForTrading
+        );
+        // 卖家不能购买自己的挂单
+        require!(
+            ctx.accounts.buyer.key() != ctx.accounts.listing.seller,
+            TournamentError::InvalidBetRecipient
+        );
+        
+        // 买家向卖家支付标价
+        transfer_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.buyer_wanzi_token.to_account_info(),
+            &ctx.accounts.wanzi_mint,
+            ctx.accounts.seller_wanzi_token.to_account_info(),
+            ctx.accounts.buyer.to_account_info(),
+            &[],
+            ctx.accounts.listing.price,
+        )?;
+        
+        let bet_key = ctx.accounts.bet.key();
+        let seeds = &[
+            b"listing".as_ref(),
+            bet_key.as_ref(),
+            &[ctx.accounts.listing.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // 将托管的下注NFT交付给买家
+        transfer_tokens(
+            ctx.accounts.nft_token_program.to_account_info(),
+            ctx.accounts.escrow_bet_nft.to_account_info(),
+            &ctx.accounts.bet_mint,
+            ctx.accounts.buyer_bet_nft.to_account_info(),
+            ctx.accounts.listing.to_account_info(),
+            signer,
+            1,
+        )?;
+        
+        // 关闭托管账户，租金返还给卖家
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.nft_token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_bet_nft.to_account_info(),
+                destination: ctx.accounts.seller.to_account_info(),
+                authority: ctx.accounts.listing.to_account_info(),
+            },
+            signer,
+        ))?;
+        
+        // 更新下注持有者
+        ctx.accounts.bet.user = ctx.accounts.buyer.key();
+        
+        Ok(())
+    }
+
+    /// 创建数值市场
+    /// 在轮次上开设大小盘或胜分差区间等以数值结果结算的市场
+    pub fn create_market(
+        ctx: Context<CreateMarket>,
+        market_index: u8,
+        name: String,
+        kind: MarketKind,
+        line: i64,
+        bucket_bounds: Vec<i64>,
+    ) -> Result<()> {
+        let mut favorite_team = Pubkey::default();
+        let mut underdog_team = Pubkey::default();
+        let outcome_count = match kind {
+            MarketKind::OverUnder => {
+                // 盘口以半分为单位，整数盘口在结果恰好等于盘口时走盘
+                require!(line >= 0, TournamentError::InvalidMarketLine);
+                require!(bucket_bounds.is_empty(), TournamentError::InvalidMarketBuckets);
+                2
+            }
+            MarketKind::Handicap => {
+                // 热门方需要赢超过让分才算赢盘
+                require!(line > 0, TournamentError::InvalidMarketLine);
+                require!(bucket_bounds.is_empty(), TournamentError::InvalidMarketBuckets);
+                let (favorite, underdog) = match (&ctx.accounts.favorite_team, &ctx.accounts.underdog_team) {
+                    (Some(favorite), Some(underdog)) => (favorite, underdog),
+                    _ => return err!(TournamentError::InvalidTeam),
+                };
+                // 两支队伍必须属于该轮次且不能相同
+                require!(
+                    favorite.round == ctx.accounts.round.key() && underdog.round == ctx.accounts.round.key(),
+                    TournamentError::TeamNotInRound
+                );
+                require!(favorite.key() != underdog.key(), TournamentError::InvalidTeam);
+                favorite_team = favorite.key();
+                underdog_team = underdog.key();
+                2
+            }
+            MarketKind::MarginBuckets => {
+                // 区间上界必须严格递增，区间数量为上界数量加一
+                require!(
+                    !bucket_bounds.is_empty() && bucket_bounds.len() < MAX_MARKET_OUTCOMES,
+                    TournamentError::InvalidMarketBuckets
+                );
+                require!(
+                    bucket_bounds.windows(2).all(|w| w[0] < w[1]),
+                    TournamentError::InvalidMarketBuckets
+                );
+                bucket_bounds.len() as u8 + 1
+            }
+        };
+        
+        let market = &mut ctx.accounts.market;
+        // 关联到特定赛事
+        market.tournament = ctx.accounts.tournament.key();
+        // 关联到特定轮次
+        market.round = ctx.accounts.round.key();
+        // 设置市场序号
+        market.market_index = market_index;
+        // 设置市场名称
+        market.name = name;
+        // 设置市场类型
+        market.kind = kind;
+        // 设置盘口
+        market.line = line;
+        // 设置区间上界
+        market.bucket_bounds = bucket_bounds;
+        // 设置让分盘的热门方和冷门方
+        market.favorite_team = favorite_team;
+        market.underdog_team = underdog_team;
+        // 设置选项数量
+        market.outcome_count = outcome_count;
+        // 初始化各选项下注额
+        market.outcome_totals = [0; MAX_MARKET_OUTCOMES];
+        market.total_bets = 0;
+        // 初始化为未出结果状态
+        market.is_resolved = false;
+        market.is_push = false;
+        market.result = 0;
+        market.winning_outcome = 0;
+        // 记录创建时间
+        market.created_at = Clock::get()?.unix_timestamp;
+        // 记录PDA bump
+        market.bump = ctx.bumps.market;
+        Ok(())
+    }
+
+    /// 数值市场下注
+    /// 用户选择市场的一个选项下注，使用的代币与队伍下注相同
+    pub fn place_market_bet(
+        ctx: Context<PlaceMarketBet>,
+        amount: u64,
+        outcome: u8,
+    ) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let market = &mut ctx.accounts.market;
+        
+        // 检查赛事是否激活
+        require!(tournament.is_active, TournamentError::TournamentNotActive);
+        // 检查轮次是否激活
+        require!(ctx.accounts.round.is_active, TournamentError::RoundNotActive);
+        // 检查市场是否已出结果
+        require!(!market.is_resolved, TournamentError::MarketAlreadyResolved);
+        // 检查选项是否有效
+        require!(outcome < market.outcome_count, TournamentError::InvalidMarketOutcome);
+        
+        let bet = &mut ctx.accounts.bet;
+        // 关联到特定赛事
+        bet.tournament = tournament.key();
+        // 关联到特定轮次
+        bet.round = ctx.accounts.round.key();
+        // 市场下注不关联队伍
+        bet.team = Pubkey::default();
+        // 记录下注用户
+        bet.user = ctx.accounts.user.key();
+        // 记录下注金额
+        bet.amount = amount;
+        // 记录下注时间
+        bet.created_at = Clock::get()?.unix_timestamp;
+        // 初始化为未结算状态
+        bet.is_settled = false;
+        // 初始化为非获胜状态
+        bet.is_winner = false;
+        // 记录代表该下注的NFT铸造器
+        bet.mint = ctx.accounts.bet_mint.key();
+        // 关联到特定市场
+        bet.market = market.key();
+        // 记录选择的选项
+        bet.outcome = outcome;
+        // 初始化为未领取补偿
+        bet.compensation_claimed = false;
+        
+        // 根据赛事是否质押决定使用哪种代币下注
+        let received = if tournament.is_staked {
+            // 检查用户是否有足够的vote代币
+            require!(
+                ctx.accounts.user_vote_token.amount >= amount,
+                TournamentError::InsufficientTokenBalance
+            );
+            deposit_tokens(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.user_vote_token.to_account_info(),
+                &ctx.accounts.vote_mint,
+                &mut ctx.accounts.tournament_vote_token,
+                ctx.accounts.user.to_account_info(),
+                amount,
+            )?
+        } else if tournament.is_sol_currency() {
+            // 使用原生SOL下注，lamports转入程序拥有的托管账户
+            let sol_vault = ctx
+                .accounts
+                .sol_vault
+                .as_mut()
+                .ok_or(TournamentError::SolVaultRequired)?;
+            sol_vault.tournament = tournament.key();
+            sol_vault.bump = ctx.bumps.sol_vault.ok_or(TournamentError::SolVaultRequired)?;
+            deposit_lamports(
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.user.to_account_info(),
+                sol_vault.to_account_info(),
+                amount,
+            )?
+        } else {
+            let (Some(user_currency_token), Some(currency_vault), Some(currency_mint)) = (
+                ctx.accounts.user_currency_token.as_ref(),
+                ctx.accounts.currency_vault.as_mut(),
+                ctx.accounts.currency_mint.as_ref(),
+            ) else {
+                return err!(TournamentError::CurrencyAccountsRequired);
+            };
+            // 检查用户是否有足够的下注代币
+            require!(
+                user_currency_token.amount >= amount,
+                TournamentError::InsufficientTokenBalance
+            );
+            deposit_tokens(
+                ctx.accounts.currency_token_program.to_account_info(),
+                user_currency_token.to_account_info(),
+                currency_mint,
+                currency_vault,
+                ctx.accounts.user.to_account_info(),
+                amount,
+            )?
+        };
+        // 按实际到账数量记录下注
+        bet.amount = received;
+        
+        // 累加市场下注额
+        let outcome_total = &mut market.outcome_totals[outcome as usize];
+        *outcome_total = outcome_total.checked_add(received).ok_or(TournamentError::Overflow)?;
+        market.total_bets = market.total_bets.checked_add(received).ok_or(TournamentError::Overflow)?;
+        
+        // 为该下注铸造1-of-1 NFT，持有NFT即持有下注
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        BetNft {
+            mint: ctx.accounts.bet_mint.to_account_info(),
+            token: ctx.accounts.user_bet_nft.to_account_info(),
+            metadata: ctx.accounts.bet_metadata.to_account_info(),
+            master_edition: ctx.accounts.bet_master_edition.to_account_info(),
+            tournament: tournament.to_account_info(),
+            payer: ctx.accounts.user.to_account_info(),
+            token_program: ctx.accounts.nft_token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        }
+        .mint(signer, &market.name, &market.key(), ctx.accounts.bet.amount)?;
+        
+        // 有下注后手续费不能再修改
+        ctx.accounts.tournament.fees_locked = true;
+        
+        Ok(())
+    }
+
+    /// 报告比分
+    /// 赛事管理员记录市场的数值结果(如总分、胜分差)，并确定获胜选项
+    pub fn report_score(
+        ctx: Context<ReportScore>,
+        value: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .round
+            .check_score_reporting(Clock::get()?.unix_timestamp)?;
+        let market = &mut ctx.accounts.market;
+        // 检查市场是否已出结果
+        require!(!market.is_resolved, TournamentError::MarketAlreadyResolved);
+        // 让分盘根据队伍比分结算
+        require!(market.kind != MarketKind::Handicap, TournamentError::InvalidMarketKind);
+        
+        // 记录数值结果和获胜选项
+        market.resolve(value);
+        
+        Ok(())
+    }
+
+    /// 报告队伍比分
+    /// 赛事管理员记录队伍在该轮次的得分，用于让分盘结算
+    pub fn report_team_score(
+        ctx: Context<ReportTeamScore>,
+        score: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .round
+            .check_score_reporting(Clock::get()?.unix_timestamp)?;
+        let team = &mut ctx.accounts.team;
+        // 比分只能报告一次
+        require!(!team.is_scored, TournamentError::TeamAlreadyScored);
+        
+        team.score = score;
+        team.is_scored = true;
+        
+        Ok(())
+    }
+
+    /// 结算让分盘
+    /// 根据两队比分计算胜分差并与让分比较，恰好等于让分时走盘退款
+    pub fn resolve_handicap_market(ctx: Context<ResolveHandicapMarket>) -> Result<()> {
+        let favorite_team = &ctx.accounts.favorite_team;
+        let underdog_team = &ctx.accounts.underdog_team;
+        let market = &mut ctx.accounts.market;
+        
+        // 检查市场是否已出结果
+        require!(!market.is_resolved, TournamentError::MarketAlreadyResolved);
+        require!(market.kind == MarketKind::Handicap, TournamentError::InvalidMarketKind);
+        // 两队比分都必须已报告
+        require!(
+            favorite_team.is_scored && underdog_team.is_scored,
+            TournamentError::TeamNotScored
+        );
+        
+        // 热门方的胜分差，可能为负
+        let margin = i64::try_from(favorite_team.score)
+            .ok()
+            .zip(i64::try_from(underdog_team.score).ok())
+            .and_then(|(favorite, underdog)| favorite.checked_sub(underdog))
+            .ok_or(TournamentError::Overflow)?;
+        market.resolve(margin);
+        
+        Ok(())
+    }
+
+    /// 配置结果预言机
+    /// 赛事管理员指定预言机程序、结果发布者和结果的最长有效期，之后完成轮次必须提供该程序拥有且由该发布者发布的结果账户
+    /// 预言机配置只能设置一次，之后不能修改或关闭，防止赛事方在轮次进行中绕过预言机
+    pub fn configure_oracle(
+        ctx: Context<ConfigureOracle>,
+        oracle_program: Pubkey,
+        oracle_publisher: Pubkey,
+        max_staleness: i64,
+    ) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        // 预言机配置一经设置永久生效
+        require!(
+            tournament.oracle_program == Pubkey::default(),
+            TournamentError::OracleConfigLocked
+        );
+        // 预言机程序和发布者都必须指定，有效期必须大于0
+        require!(
+            oracle_program != Pubkey::default()
+                && oracle_publisher != Pubkey::default()
+                && max_staleness > 0,
+            TournamentError::InvalidOracleConfig
+        );
+        
+        tournament.oracle_program = oracle_program;
+        tournament.oracle_publisher = oracle_publisher;
+        tournament.oracle_max_staleness = max_staleness;
+        
+        Ok(())
+    }
+
+    /// 配置结果裁定人
+    /// 赛事管理员设置裁定人集合、阈值(M-of-N)、提交/揭示期时长和裁定人需质押的matchp数量
+    /// 之后轮次只能由裁定人先提交承诺再揭示结果完成
+    pub fn configure_resolvers(
+        ctx: Context<ConfigureResolvers>,
+        resolvers: Vec<Pubkey>,
+        threshold: u8,
+        commit_period: i64,
+        reveal_period: i64,
+        stake_amount: u64,
+    ) -> Result<()> {
+        // 检查裁定人数量和阈值
+        require!(
+            !resolvers.is_empty() && resolvers.len() <= MAX_RESOLVERS,
+            TournamentError::InvalidResolverSet
+        );
+        require!(
+            threshold > 0 && threshold as usize <= resolvers.len(),
+            TournamentError::InvalidResolverThreshold
+        );
+        // 裁定人不能重复
+        for (i, resolver) in resolvers.iter().enumerate() {
+            require!(!resolvers[..i].contains(resolver), TournamentError::InvalidResolverSet);
+        }
+        // 提交期和揭示期都必须大于0
+        require!(
+            commit_period > 0 && reveal_period > 0,
+            TournamentError::InvalidResolverPeriod
+        );
+        
+        let resolver_set = &mut ctx.accounts.resolver_set;
+        // 关联到特定赛事
+        resolver_set.tournament = ctx.accounts.tournament.key();
+        // 设置裁定人集合
+        resolver_set.resolvers = resolvers;
+        // 设置阈值
+        resolver_set.threshold = threshold;
+        // 设置提交期和揭示期时长
+        resolver_set.commit_period = commit_period;
+        resolver_set.reveal_period = reveal_period;
+        // 设置裁定人需质押的matchp数量
+        resolver_set.stake_amount = stake_amount;
+        // 记录PDA bump
+        resolver_set.bump = ctx.bumps.resolver_set;
+        
+        // 赛事改为多签裁定
+        ctx.accounts.tournament.resolver_threshold = threshold;
+        
+        Ok(())
+    }
+
+    /// 裁定人质押
+    /// 裁定人向赛事托管账户质押matchp，质押不足时不能提交结果承诺
+    pub fn stake_resolver(ctx: Context<StakeResolver>, amount: u64) -> Result<()> {
+        // 检查质押数量
+        require!(amount > 0, TournamentError::InvalidStakeAmount);
+        // 检查调用者是否为裁定人
+        require!(
+            ctx.accounts.resolver_set.resolvers.contains(&ctx.accounts.resolver.key()),
+            TournamentError::NotResolver
+        );
+        
+        // 转入质押的matchp，按实际到账数量记录
+        let received = deposit_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.resolver_matchp_token.to_account_info(),
+            &ctx.accounts.matchp_mint,
+            &mut ctx.accounts.resolver_stake_vault,
+            ctx.accounts.resolver.to_account_info(),
+            amount,
+        )?;
+        
+        let resolver_stake = &mut ctx.accounts.resolver_stake;
+        if resolver_stake.resolver == Pubkey::default() {
+            // 首次质押时初始化
+            resolver_stake.tournament = ctx.accounts.tournament.key();
+            resolver_stake.resolver = ctx.accounts.resolver.key();
+            resolver_stake.bump = ctx.bumps.resolver_stake;
+        }
+        resolver_stake.amount = resolver_stake
+            .amount
+            .checked_add(received)
+            .ok_or(TournamentError::Overflow)?;
+        
+        Ok(())
+    }
+
+    /// 取回裁定人质押
+    /// 所有已提交的承诺都揭示或被罚没后，裁定人才能取回质押
+    pub fn unstake_resolver(ctx: Context<UnstakeResolver>, amount: u64) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let resolver_stake = &mut ctx.accounts.resolver_stake;
+        
+        // 检查质押数量
+        require!(
+            amount > 0 && amount <= resolver_stake.amount,
+            TournamentError::InvalidStakeAmount
+        );
+        // 有待揭示的承诺时质押被锁定
+        require!(
+            resolver_stake.pending_reveals == 0,
+            TournamentError::ResolverStakeLocked
+        );
+        
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // 退还质押的matchp
+        transfer_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.resolver_stake_vault.to_account_info(),
+            &ctx.accounts.matchp_mint,
+            ctx.accounts.resolver_matchp_token.to_account_info(),
+            tournament.to_account_info(),
+            signer,
+            amount,
+        )?;
+        
+        resolver_stake.amount = resolver_stake
+            .amount
+            .checked_sub(amount)
+            .ok_or(TournamentError::Overflow)?;
+        
+        Ok(())
+    }
+
+    /// 提交结果承诺
+    /// 裁定人在提交期内提交hash(裁定人, 轮次, 获胜队伍, salt)，首个承诺开启该轮次的提交期
+    /// 承诺绑定裁定人和轮次，其他裁定人复制承诺后无法用同样的揭示内容通过校验
+    pub fn commit_result(ctx: Context<CommitResult>, commitment: [u8; 32]) -> Result<()> {
+        let resolver_key = ctx.accounts.resolver.key();
+        let resolver_set = &ctx.accounts.resolver_set;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // 检查轮次是否激活
+        require!(ctx.accounts.round.is_active, TournamentError::RoundNotActive);
+        // 检查调用者是否为裁定人
+        require!(
+            resolver_set.resolvers.contains(&resolver_key),
+            TournamentError::NotResolver
+        );
+        // 检查裁定人质押是否足够
+        let resolver_stake = &mut ctx.accounts.resolver_stake;
+        require!(
+            resolver_stake.amount >= resolver_set.stake_amount,
+            TournamentError::InsufficientResolverStake
+        );
+        
+        let resolution = &mut ctx.accounts.resolution;
+        if resolution.round == Pubkey::default() {
+            // 首个承诺时初始化并开启提交期
+            resolution.round = ctx.accounts.round.key();
+            resolution.commit_deadline = current_timestamp
+                .checked_add(resolver_set.commit_period)
+                .ok_or(TournamentError::Overflow)?;
+            resolution.reveal_deadline = resolution
+                .commit_deadline
+                .checked_add(resolver_set.reveal_period)
+                .ok_or(TournamentError::Overflow)?;
+            resolution.bump = ctx.bumps.resolution;
+        }
+        // 检查是否在提交期内
+        require!(
+            current_timestamp < resolution.commit_deadline,
+            TournamentError::CommitPhaseClosed
+        );
+        // 每个裁定人只能提交一次
+        require!(
+            !resolution.votes.iter().any(|vote| vote.resolver == resolver_key),
+            TournamentError::ResolverAlreadyVoted
+        );
+        resolution.votes.push(ResolverVote {
+            resolver: resolver_key,
+            commitment,
+            team: Pubkey::default(),
+            is_revealed: false,
+            is_penalized: false,
+        });
+        
+        // 揭示或罚没前锁定质押
+        resolver_stake.pending_reveals = resolver_stake
+            .pending_reveals
+            .checked_add(1)
+            .ok_or(TournamentError::Overflow)?;
+        
+        Ok(())
+    }
+
+    /// 揭示结果
+    /// 裁定人在揭示期内公开获胜队伍和salt，与承诺一致时计票，同一队伍的票数达到阈值时轮次完成
+    pub fn reveal_result(ctx: Context<RevealResult>, salt: [u8; 32]) -> Result<()> {
+        let resolver_key = ctx.accounts.resolver.key();
+        let team_key = ctx.accounts.team.key();
+        let round_key = ctx.accounts.round.key();
+        let threshold = ctx.accounts.resolver_set.threshold;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        let resolution = &mut ctx.accounts.resolution;
+        
+        // 检查是否在揭示期内
+        require!(
+            current_timestamp >= resolution.commit_deadline,
+            TournamentError::CommitPhaseOpen
+        );
+        require!(
+            current_timestamp < resolution.reveal_deadline,
+            TournamentError::RevealPhaseClosed
+        );
+        
+        let vote = resolution
+            .votes
+            .iter_mut()
+            .find(|vote| vote.resolver == resolver_key)
+            .ok_or(TournamentError::CommitmentNotFound)?;
+        // 每个承诺只能揭示一次
+        require!(!vote.is_revealed, TournamentError::AlreadyRevealed);
+        // 校验揭示内容与承诺一致
+        require!(
+            ResolverVote::commitment_for(&resolver_key, &round_key, &team_key, &salt)
+                == vote.commitment,
+            TournamentError::InvalidReveal
+        );
+        vote.team = team_key;
+        vote.is_revealed = true;
+        
+        // 解除一笔质押锁定
+        let resolver_stake = &mut ctx.accounts.resolver_stake;
+        resolver_stake.pending_reveals = resolver_stake.pending_reveals.saturating_sub(1);
+        
+        // 同一队伍的揭示票数达到阈值时完成轮次
+        let team_votes = resolution
+            .votes
+            .iter()
+            .filter(|vote| vote.is_revealed && vote.team == team_key)
+            .count();
+        if ctx.accounts.round.is_active && team_votes >= threshold as usize {
+            let dispute_deadline = current_timestamp
+                .checked_add(ctx.accounts.tournament.dispute_period)
+                .ok_or(TournamentError::Overflow)?;
+            ctx.accounts.round.complete(&mut ctx.accounts.team, dispute_deadline);
+            ctx.accounts.tournament.record_round_result(dispute_deadline)?;
+        }
+        
+        Ok(())
+    }
+
+    /// 罚没未揭示的裁定人
+    /// 揭示期结束后任何人都可调用，将提交了承诺但未揭示的裁定人的质押罚没到国库
+    pub fn penalize_unrevealed(ctx: Context<PenalizeUnrevealed>) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let resolver_key = ctx.accounts.resolver_stake.resolver;
+        let resolution = &mut ctx.accounts.resolution;
+        
+        // 检查揭示期是否已结束
+        require!(
+            Clock::get()?.unix_timestamp >= resolution.reveal_deadline,
+            TournamentError::RevealPhaseOpen
+        );
+        
+        let vote = resolution
+            .votes
+            .iter_mut()
+            .find(|vote| vote.resolver == resolver_key)
+            .ok_or(TournamentError::CommitmentNotFound)?;
+        // 只能罚没未揭示且未被罚没的承诺
+        require!(
+            !vote.is_revealed && !vote.is_penalized,
+            TournamentError::NotPenalizable
+        );
+        vote.is_penalized = true;
+        
+        // 罚没数量为赛事要求的质押数量，不超过实际质押
+        let resolver_stake = &mut ctx.accounts.resolver_stake;
+        let penalty = resolver_stake.amount.min(ctx.accounts.resolver_set.stake_amount);
+        resolver_stake.amount = resolver_stake
+            .amount
+            .checked_sub(penalty)
+            .ok_or(TournamentError::Overflow)?;
+        resolver_stake.pending_reveals = resolver_stake.pending_reveals.saturating_sub(1);
+        
+        if penalty > 0 {
+            let authority_key = tournament.authority;
+            let seeds = &[
+                b"tournament".as_ref(),
+                authority_key.as_ref(),
+                &[tournament.bump],
+            ];
+            let signer = &[&seeds[..]];
+            
+            // 罚没的matchp转入国库
+            transfer_tokens(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.resolver_stake_vault.to_account_info(),
+                &ctx.accounts.matchp_mint,
+                ctx.accounts.treasury.to_account_info(),
+                tournament.to_account_info(),
+                signer,
+                penalty,
+            )?;
+        }
+        
+        Ok(())
+    }
+
+    /// 提取国库资金
+    /// 合约管理员从国库提取罚没所得和没收的争议保证金，国库按代币区分
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        // 检查提取数量
+        require!(
+            amount > 0 && amount <= ctx.accounts.treasury.amount,
+            TournamentError::InsufficientTokenBalance
+        );
+        
+        let seeds = &[b"state".as_ref(), &[ctx.bumps.state]];
+        let signer = &[&seeds[..]];
+        
+        transfer_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.mint,
+            ctx.accounts.destination.to_account_info(),
+            ctx.accounts.state.to_account_info(),
+            signer,
+            amount,
+        )?;
+        
+        Ok(())
+    }
+
+    /// 配置争议期
+    /// 赛事管理员设置轮次完成后的争议期时长和发起争议所需的保证金
+    pub fn configure_dispute(
+        ctx: Context<ConfigureDispute>,
+        dispute_period: i64,
+        dispute_bond: u64,
+    ) -> Result<()> {
+        // 检查争议期时长
+        require!(dispute_period >= 0, TournamentError::InvalidDisputePeriod);
+        // 开启争议期时保证金必须大于0
+        require!(
+            dispute_period == 0 || dispute_bond > 0,
+            TournamentError::InvalidDisputeBond
+        );
+        
+        let tournament = &mut ctx.accounts.tournament;
+        // 有未裁决的争议时不能修改争议配置
+        require!(tournament.open_disputes == 0, TournamentError::DisputeInProgress);
+        tournament.dispute_period = dispute_period;
+        tournament.dispute_bond = dispute_bond;
+        
+        Ok(())
+    }
+
+    /// 发起争议
+    /// 争议期内任何用户缴纳保证金对轮次结果提出异议，争议裁决前轮次暂停结算
+    /// 质押赛事的保证金为matchp，否则为wanzi
+    pub fn challenge_result(ctx: Context<ChallengeResult>) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let round = &mut ctx.accounts.round;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // 检查轮次是否已完成
+        require!(round.is_completed, TournamentError::RoundNotCompleted);
+        // 检查是否在争议期内
+        require!(
+            current_timestamp < round.dispute_deadline,
+            TournamentError::DisputeWindowClosed
+        );
+        // 提出的获胜队伍不能是当前获胜队伍
+        require!(
+            ctx.accounts.proposed_team.key() != round.winner_team,
+            TournamentError::InvalidTeam
+        );
+        // 检查保证金代币
+        let bond_mint = if tournament.is_staked {
+            ctx.accounts.state.matchp_mint
+        } else {
+            ctx.accounts.state.wanzi_mint
+        };
+        require!(
+            ctx.accounts.bond_mint.key() == bond_mint,
+            TournamentError::InvalidBondMint
+        );
+        
+        // 转移保证金到争议托管账户，按实际到账数量记录以便全额退还
+        let bond_amount = deposit_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.challenger_bond_token.to_account_info(),
+            &ctx.accounts.bond_mint,
+            &mut ctx.accounts.dispute_bond_vault,
+            ctx.accounts.challenger.to_account_info(),
+            tournament.dispute_bond,
+        )?;
+        
+        let dispute = &mut ctx.accounts.dispute;
+        // 关联到特定赛事
+        dispute.tournament = tournament.key();
+        // 关联到特定轮次
+        dispute.round = round.key();
+        // 记录发起人
+        dispute.challenger = ctx.accounts.challenger.key();
+        // 记录原获胜队伍
+        dispute.original_team = round.winner_team;
+        // 记录提出的获胜队伍
+        dispute.proposed_team = ctx.accounts.proposed_team.key();
+        // 记录保证金
+        dispute.bond_mint = bond_mint;
+        dispute.bond_amount = bond_amount;
+        // 记录发起时间
+        dispute.created_at = current_timestamp;
+        // 初始化为未裁决状态
+        dispute.is_resolved = false;
+        dispute.is_overturned = false;
+        // 记录发起时的治理配置，裁决只使用记录的法定票数和截止时间
+        dispute.quorum = tournament.governance_quorum;
+        dispute.vote_deadline = if tournament.governance_quorum > 0 {
+            current_timestamp
+                .checked_add(tournament.governance_vote_period)
+                .ok_or(TournamentError::Overflow)?
+        } else {
+            0
+        };
+        dispute.overturn_votes = 0;
+        dispute.uphold_votes = 0;
+        // 记录PDA bump
+        dispute.bump = ctx.bumps.dispute;
+        
+        // 轮次进入争议状态，暂停结算
+        round.is_disputed = true;
+        
+        // 记录赛事未裁决的争议数量，期间锁定争议和治理配置
+        let tournament = &mut ctx.accounts.tournament;
+        tournament.open_disputes = tournament
+            .open_disputes
+            .checked_add(1)
+            .ok_or(TournamentError::Overflow)?;
+        
+        Ok(())
+    }
+
+    /// 裁决争议
+    /// 合约管理员或争议仲裁员维持原结果(发起人保证金没收到国库)或推翻原结果(更正获胜队伍并退还保证金)
+    /// 发起时开启了代币投票治理的争议只能通过finalize_dispute_vote裁决
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        overturn: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.ruling.dispute.quorum == 0,
+            TournamentError::GovernanceVoteRequired
+        );
+        
+        ctx.accounts.ruling.apply(overturn)
+    }
+
+    /// 配置代币投票治理
+    /// 赛事管理员为质押赛事开启由vote持有者投票裁决争议，设置投票期和法定票数
+    pub fn configure_governance(
+        ctx: Context<ConfigureGovernance>,
+        vote_period: i64,
+        quorum: u64,
+    ) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        // 有未裁决的争议时不能修改治理配置，进行中的投票使用发起争议时的配置
+        require!(tournament.open_disputes == 0, TournamentError::DisputeInProgress);
+        // 只有质押赛事才有vote持有者
+        require!(
+            quorum == 0 || tournament.is_staked,
+            TournamentError::TournamentNotStaked
+        );
+        // 开启治理时投票期必须大于0
+        require!(
+            quorum == 0 || vote_period > 0,
+            TournamentError::InvalidVotePeriod
+        );
+        
+        tournament.governance_vote_period = vote_period;
+        tournament.governance_quorum = quorum;
+        
+        Ok(())
+    }
+
+    /// 对争议投票
+    /// vote持有者锁定vote代币，按数量加权支持推翻或维持原结果，代币在争议裁决后才能取回
+    pub fn cast_dispute_vote(
+        ctx: Context<CastDisputeVote>,
+        overturn: bool,
+        amount: u64,
+    ) -> Result<()> {
+        let dispute = &mut ctx.accounts.dispute;
+        
+        // 检查投票数量
+        require!(amount > 0, TournamentError::InvalidVoteAmount);
+        // 检查争议是否已裁决
+        require!(!dispute.is_resolved, TournamentError::DisputeAlreadyResolved);
+        // 检查是否在投票期内
+        require!(
+            Clock::get()?.unix_timestamp < dispute.vote_deadline,
+            TournamentError::VotingClosed
+        );
+        
+        // 锁定vote代币
+        transfer_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.voter_vote_token.to_account_info(),
+            &ctx.accounts.vote_mint,
+            ctx.accounts.governance_vault.to_account_info(),
+            ctx.accounts.voter.to_account_info(),
+            &[],
+            amount,
+        )?;
+        
+        // 累加票数
+        if overturn {
+            dispute.overturn_votes = dispute.overturn_votes.checked_add(amount).ok_or(TournamentError::Overflow)?;
+        } else {
+            dispute.uphold_votes = dispute.uphold_votes.checked_add(amount).ok_or(TournamentError::Overflow)?;
+        }
+        
+        let vote = &mut ctx.accounts.dispute_vote;
+        // 关联到特定争议
+        vote.dispute = dispute.key();
+        // 记录投票人
+        vote.voter = ctx.accounts.voter.key();
+        // 记录锁定的vote数量
+        vote.amount = amount;
+        // 记录投票方向
+        vote.overturn = overturn;
+        // 记录PDA bump
+        vote.bump = ctx.bumps.dispute_vote;
+        
+        Ok(())
+    }
+
+    /// 结束争议投票
+    /// 投票期结束后任何人都可调用：达到法定票数且推翻票多于维持票时推翻原结果，否则维持
+    pub fn finalize_dispute_vote(ctx: Context<FinalizeDisputeVote>) -> Result<()> {
+        let dispute = &ctx.accounts.ruling.dispute;
+        
+        // 检查争议发起时是否开启了代币投票治理
+        require!(dispute.quorum > 0, TournamentError::GovernanceNotEnabled);
+        // 检查投票期是否已结束
+        require!(
+            Clock::get()?.unix_timestamp >= dispute.vote_deadline,
+            TournamentError::VotingOpen
+        );
+        
+        let total_votes = dispute
+            .overturn_votes
+            .checked_add(dispute.uphold_votes)
+            .ok_or(TournamentError::Overflow)?;
+        let overturn = total_votes >= dispute.quorum
+            && dispute.overturn_votes > dispute.uphold_votes;
+        
+        ctx.accounts.ruling.apply(overturn)
+    }
+
+    /// 取回争议投票
+    /// 争议裁决后投票人取回锁定的vote代币并关闭投票记录
+    pub fn withdraw_dispute_vote(ctx: Context<WithdrawDisputeVote>) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        
+        // 争议裁决后才能取回
+        require!(ctx.accounts.dispute.is_resolved, TournamentError::DisputeNotResolved);
+        
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // 退还锁定的vote代币
+        transfer_tokens(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.governance_vault.to_account_info(),
+            &ctx.accounts.vote_mint,
+            ctx.accounts.voter_vote_token.to_account_info(),
+            tournament.to_account_info(),
+            signer,
+            ctx.accounts.dispute_vote.amount,
+        )?;
+        
+        Ok(())
+    }
+
+    /// 配置质押罚没
+    /// 合约管理员设置赛事方质押的罚没比例(基点)和轮次的裁定期限
+    /// 结果被推翻或轮次超过裁定期限未完成时，可罚没质押补偿下注者
+    pub fn configure_slashing(
+        ctx: Context<ConfigureSlashing>,
+        slash_bps: u16,
+        resolution_period: i64,
+    ) -> Result<()> {
+        // 检查罚没比例和裁定期限
+        require!(
+            slash_bps as u64 <= BPS_DENOMINATOR && resolution_period >= 0,
+            TournamentError::InvalidSlashConfig
+        );
+        
+        let tournament = &mut ctx.accounts.tournament;
+        // 有待执行的罚没时不能修改罚没配置
+        require!(tournament.pending_slashes == 0, TournamentError::UnsettledRounds);
+        tournament.slash_bps = slash_bps;
+        tournament.resolution_period = resolution_period;
+        
+        Ok(())
+    }
+
+    /// 罚没赛事方质押
+    /// 轮次结果被推翻或轮次因超过裁定期限而过期后任何人都可调用，每个轮次只罚没一次
+    /// 结果被推翻时罚没的一部分奖励给争议发起人，其余进入轮次补偿池由该轮次的下注者按比例领取，轮次没有下注时转入国库
+    pub fn slash_stake(ctx: Context<SlashStake>) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let round = &ctx.accounts.round;
+        
+        // 质押仍在合约中才能罚没
+        require!(tournament.is_staked, TournamentError::TournamentNotStaked);
+        require!(tournament.slash_bps > 0, TournamentError::SlashingNotEnabled);
+        // 每个轮次只罚没一次
+        require!(!round.is_slashed, TournamentError::RoundAlreadySlashed);
+        
+        // 结果被推翻或轮次已过期
+        require!(round.slash_pending, TournamentError::RoundNotSlashable);
+        
+        let slash_amount = u64::try_from(
+            tournament.stake_amount as u128 * tournament.slash_bps as u128
+                / BPS_DENOMINATOR as u128,
+        )
+        .map_err(|_| TournamentError::Overflow)?;
+        let total_bets = round
+            .total_currency_bets
+            .checked_add(round.total_vote_bets)
+            .ok_or(TournamentError::Overflow)?;
+        
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // 结果被推翻导致的罚没，按比例奖励争议发起人
+        let mut challenger_reward = 0;
+        if !round.is_expired {
+            let dispute = ctx
+                .accounts
+                .dispute
+                .as_ref()
+                .ok_or(TournamentError::ChallengerAccountRequired)?;
+            require!(dispute.is_overturned, TournamentError::RoundNotSlashable);
+            let challenger_token = ctx
+                .accounts
+                .challenger_matchp_token
+                .as_ref()
+                .ok_or(TournamentError::ChallengerAccountRequired)?;
+            require!(
+                challenger_token.owner == dispute.challenger,
+                TournamentError::InvalidTokenOwner
+            );
+            
+            challenger_reward = slash_amount
+                .checked_mul(CHALLENGER_REWARD_BPS)
+                .ok_or(TournamentError::Overflow)?
+                / BPS_DENOMINATOR;
+            if challenger_reward > 0 {
+                transfer_tokens(
+                    ctx.accounts.token_program.to_account_info(),
+                    ctx.accounts.tournament_matchp_token.to_account_info(),
+                    &ctx.accounts.matchp_mint,
+                    challenger_token.to_account_info(),
+                    tournament.to_account_info(),
+                    signer,
+                    challenger_reward,
+                )?;
+            }
+        }
+        let remaining_slash = slash_amount
+            .checked_sub(challenger_reward)
+            .ok_or(TournamentError::Overflow)?;
+        
+        // 剩余部分有下注时补偿给下注者，否则转入国库
+        let destination = if total_bets > 0 {
+            ctx.accounts.compensation_vault.to_account_info()
+        } else {
+            ctx.accounts.treasury.to_account_info()
+        };
+        if remaining_slash > 0 {
+            transfer_tokens(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.tournament_matchp_token.to_account_info(),
+                &ctx.accounts.matchp_mint,
+                destination,
+                tournament.to_account_info(),
+                signer,
+                remaining_slash,
+            )?;
+        }
+        
+        // 扣减质押，关闭赛事时只返还剩余部分
+        let tournament = &mut ctx.accounts.tournament;
+        tournament.stake_amount = tournament
+            .stake_amount
+            .checked_sub(slash_amount)
+            .ok_or(TournamentError::Overflow)?;
+        
+        tournament.pending_slashes = tournament
+            .pending_slashes
+            .checked_sub(1)
+            .ok_or(TournamentError::Overflow)?;
+        
+        let round = &mut ctx.accounts.round;
+        round.slash_pending = false;
+        round.is_slashed = true;
+        if total_bets > 0 {
+            round.compensation_pool = remaining_slash;
+        }
+        
+        Ok(())
+    }
+
+    /// 标记轮次过期
+    /// 轮次超过裁定期限仍未完成时任何人都可调用，过期后下注者可申请退款
+    /// 赛事开启了罚没时，过期的轮次同时等待罚没赛事方质押
+    pub fn expire_round(ctx: Context<ExpireRound>) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        let round = &mut ctx.accounts.round;
+        
+        // 只有未完成的轮次才能过期
+        require!(round.is_active && !round.is_completed, TournamentError::RoundNotActive);
+        // 检查是否已超过裁定期限
+        require!(
+            round.resolution_deadline > 0
+                && Clock::get()?.unix_timestamp > round.resolution_deadline,
+            TournamentError::ResolutionDeadlineNotPassed
+        );
+        
+        // 关闭轮次，不再接受下注和结果
+        round.is_active = false;
+        round.is_expired = true;
+        tournament.unresolved_rounds = tournament
+            .unresolved_rounds
+            .checked_sub(1)
+            .ok_or(TournamentError::Overflow)?;
+        // 赛事方有质押且开启罚没时等待罚没
+        if tournament.is_staked && tournament.slash_bps > 0 {
+            round.slash_pending = true;
+            tournament.pending_slashes = tournament
+                .pending_slashes
+                .checked_add(1)
+                .ok_or(TournamentError::Overflow)?;
+        }
+        
+        Ok(())
+    }
+
+    /// 过期轮次退款
+    /// 下注NFT的持有者取回下注本金，退款后销毁NFT
+    /// 过期的轮次不会完成，市场下注无论市场是否出结果都按本金退款
+    pub fn refund_bet(ctx: Context<RefundBet>) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let bet = &mut ctx.accounts.bet;
+        
+        require!(ctx.accounts.round.is_expired, TournamentError::RoundNotExpired);
+        require!(!bet.is_settled, TournamentError::BetAlreadySettled);
+        
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // 按下注时使用的代币退还本金
+        if tournament.is_staked {
+            transfer_tokens(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.tournament_vote_token.to_account_info(),
+                &ctx.accounts.vote_mint,
+                ctx.accounts.user_vote_token.to_account_info(),
+                tournament.to_account_info(),
+                signer,
+                bet.amount,
+            )?;
+        } else if tournament.is_sol_currency() {
+            let sol_vault = ctx
+                .accounts
+                .sol_vault
+                .as_ref()
+                .ok_or(TournamentError::SolVaultRequired)?;
+            pay_lamports(sol_vault, &ctx.accounts.user.to_account_info(), bet.amount)?;
+        } else {
+            let (Some(user_currency_token), Some(currency_vault), Some(currency_mint)) = (
+                ctx.accounts.user_currency_token.as_ref(),
+                ctx.accounts.currency_vault.as_ref(),
+                ctx.accounts.currency_mint.as_ref(),
+            ) else {
+                return err!(TournamentError::CurrencyAccountsRequired);
+            };
+            transfer_tokens(
+                ctx.accounts.currency_token_program.to_account_info(),
+                currency_vault.to_account_info(),
+                currency_mint,
+                user_currency_token.to_account_info(),
+                tournament.to_account_info(),
+                signer,
+                bet.amount,
+            )?;
+        }
+        
+        // 销毁下注NFT，租金返还给持有者
+        metadata::burn_nft(
+            CpiContext::new(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                BurnNft {
+                    metadata: ctx.accounts.bet_metadata.to_account_info(),
+                    owner: ctx.accounts.user.to_account_info(),
+                    mint: ctx.accounts.bet_mint.to_account_info(),
+                    token: ctx.accounts.user_bet_nft.to_account_info(),
+                    edition: ctx.accounts.bet_master_edition.to_account_info(),
+                    spl_token: ctx.accounts.nft_token_program.to_account_info(),
+                },
+            ),
+            None,
+        )?;
+        
+        bet.user = ctx.accounts.user.key();
+        bet.is_settled = true;
+        
+        Ok(())
+    }
+
+    /// 领取罚没补偿
+    /// 下注持有者按下注金额占轮次总下注额的比例领取补偿池中的matchp，每笔下注只能领取一次
+    /// 未结算的下注以NFT持有者为准，已结算的下注以结算时的持有者为准
+    pub fn claim_compensation(ctx: Context<ClaimCompensation>) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let round = &ctx.accounts.round;
+        let bet = &ctx.accounts.bet;
+        
+        // 检查轮次是否有补偿
+        require!(round.compensation_pool > 0, TournamentError::NoCompensation);
+        // 每笔下注只能领取一次
+        require!(!bet.compensation_claimed, TournamentError::CompensationAlreadyClaimed);
+        
+        // 校验调用者是下注持有者
+        let user_key = ctx.accounts.user.key();
+        let is_holder = if bet.is_settled {
+            bet.user == user_key
+        } else {
+            ctx.accounts.user_bet_nft.as_ref().is_some_and(|nft| {
+                nft.mint == bet.mint && nft.owner == user_key && nft.amount == 1
+            })
+        };
+        require!(is_holder, TournamentError::NotBetOwner);
+        
+        let total_bets = round
+            .total_currency_bets
+            .checked_add(round.total_vote_bets)
+            .ok_or(TournamentError::Overflow)?;
+        let share = u64::try_from(
+            bet.amount as u128 * round.compensation_pool as u128 / total_bets as u128,
+        )
+        .map_err(|_| TournamentError::Overflow)?;
+        
+        let authority_key = tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        if share > 0 {
+            transfer_tokens(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.compensation_vault.to_account_info(),
+                &ctx.accounts.matchp_mint,
+                ctx.accounts.user_matchp_token.to_account_info(),
+                tournament.to_account_info(),
+                signer,
+                share,
+            )?;
+        }
+        
+        ctx.accounts.bet.compensation_claimed = true;
+        
+        Ok(())
+    }
+
+    /// 结算市场下注
+    /// 按彩池比例向选中获胜选项的下注NFT持有者支付奖励，结算后销毁NFT
+    /// 与队伍下注相同，须等所属轮次的争议期结束且没有未决争议
+    pub fn settle_market_bet(ctx: Context<SettleMarketBet>) -> Result<()> {
+        let bet = &mut ctx.accounts.bet;
+        let market = &ctx.accounts.market;
+        let tournament = &ctx.accounts.tournament;
+        
+        require!(market.is_resolved, TournamentError::MarketNotResolved);
+        require!(!bet.is_settled, TournamentError::BetAlreadySettled);
+        require!(bet.market == market.key(), TournamentError::InvalidBetMarket);
+        // 市场结果与轮次结果共用争议期，轮次完成、争议期结束且没有未决争议时才能结算
+        let round = &ctx.accounts.round;
+        require!(round.is_completed, TournamentError::RoundNotCompleted);
+        require!(!round.is_disputed, TournamentError::RoundDisputed);
+        require!(
+            Clock::get()?.unix_timestamp >= round.dispute_deadline,
+            TournamentError::DisputeWindowOpen
+        );
+        
+        let is_winner = !market.is_push && bet.outcome == market.winning_outcome;
+        let winning_total = market.outcome_totals[market.winning_outcome as usize];
+        let gross_amount = if market.is_push || winning_total == 0 {
+            // 走盘或无人选中获胜选项时退还所有下注
+            bet.amount
+        } else if is_winner {
+            // 获胜者按下注比例瓜分整个市场彩池
+            (bet.amount as u128)
+                .checked_mul(market.total_bets as u128)
+                .and_then(|v| v.checked_div(winning_total as u128))
+                .and_then(|v| u64::try_from(v).ok())
+                .ok_or(TournamentError::Overflow)?
+        } else {
+            0
+        };
+        // 只从VOTE赛事的获胜奖金中扣除质押奖励手续费，退款不收费
+        let fee = if is_winner && tournament.is_staked {
+            tournament.bet_fee(gross_amount)
+        } else {
+            0
+        };
+        let reward_amount = gross_amount
+            .checked_sub(fee)
+            .ok_or(TournamentError::Overflow)?;
+        
+        if gross_amount > 0 {
+            let authority_key = tournament.authority;
+            let seeds = &[
+                b"tournament".as_ref(),
+                authority_key.as_ref(),
+                &[tournament.bump],
+            ];
+            let signer = &[&seeds[..]];
+            
+            if tournament.is_staked {
+                if fee > 0 {
+                    let rewards_vault = ctx
+                        .accounts
+                        .rewards_vault
+                        .as_ref()
+                        .ok_or(TournamentError::RewardsVaultRequired)?;
+                    transfer_tokens(
+                        ctx.accounts.token_program.to_account_info(),
+                        ctx.accounts.tournament_vote_token.to_account_info(),
+                        &ctx.accounts.vote_mint,
+                        rewards_vault.to_account_info(),
+                        tournament.to_account_info(),
+                        signer,
+                        fee,
+                    )?;
+                }
+                transfer_tokens(
+                    ctx.accounts.token_program.to_account_info(),
+                    ctx.accounts.tournament_vote_token.to_account_info(),
+                    &ctx.accounts.vote_mint,
+                    ctx.accounts.user_vote_token.to_account_info(),
+                    tournament.to_account_info(),
+                    signer,
+                    reward_amount,
+                )?;
+            } else if tournament.is_sol_currency() {
+                let sol_vault = ctx
+                    .accounts
+                    .sol_vault
+                    .as_ref()
+                    .ok_or(TournamentError::SolVaultRequired)?;
+                pay_lamports(sol_vault, &ctx.accounts.user.to_account_info(), reward_amount)?;
+            } else {
+                let (Some(user_currency_token), Some(currency_vault), Some(currency_mint)) = (
+                    ctx.accounts.user_currency_token.as_ref(),
+                    ctx.accounts.currency_vault.as_ref(),
+                    ctx.accounts.currency_mint.as_ref(),
+                ) else {
+                    return err!(TournamentError::CurrencyAccountsRequired);
+                };
+                transfer_tokens(
+                    ctx.accounts.currency_token_program.to_account_info(),
+                    currency_vault.to_account_info(),
+                    currency_mint,
+                    user_currency_token.to_account_info(),
+                    tournament.to_account_info(),
+                    signer,
+                    reward_amount,
+                )?;
+            }
+        }
+        
+        // 销毁下注NFT，租金返还给持有者
+        metadata::burn_nft(
+            CpiContext::new(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                BurnNft {
+                    metadata: ctx.accounts.bet_metadata.to_account_info(),
+                    owner: ctx.accounts.user.to_account_info(),
+                    mint: ctx.accounts.bet_mint.to_account_info(),
+                    token: ctx.accounts.user_bet_nft.to_account_info(),
+                    edition: ctx.accounts.bet_master_edition.to_account_info(),
+                    spl_token: ctx.accounts.nft_token_program.to_account_info(),
+                },
+            ),
+            None,
+        )?;
+        
+        bet.user = ctx.accounts.user.key();
+        bet.is_settled = true;
+        bet.is_winner = is_winner;
+        
+        // 手续费按份额累计给质押者
+        if fee > 0 {
+            ctx.accounts.tournament.distribute_rewards(fee)?;
+        }
+        
+        Ok(())
+    }
+}
+
+/// 通过transfer_checked转移代币，兼容SPL Token与Token-2022铸造器
+pub fn transfer_tokens<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program,
+            TransferChecked {
+                from,
+                mint: mint.to_account_info(),
+                to,
+                authority,
+            },
+            signer,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+/// 将用户代币转入合约池子，返回池子实际收到的数量
+/// Token-2022转账手续费会从到账金额中扣除，池子总额必须按实际到账记录
+pub fn deposit_tokens<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &mut InterfaceAccount<'info, TokenAccount>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+) -> Result<u64> {
+    let balance_before = to.amount;
+    transfer_tokens(token_program, from, mint, to.to_account_info(), authority, &[], amount)?;
+    to.reload()?;
+    let received = to
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(TournamentError::Overflow)?;
+    require!(received > 0, TournamentError::NothingReceived);
+    Ok(received)
+}
+
+/// 将用户的lamports转入SOL托管账户，返回转入的数量
+pub fn deposit_lamports<'info>(
+    system_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    sol_vault: AccountInfo<'info>,
+    amount: u64,
+) -> Result<u64> {
+    require!(amount > 0, TournamentError::NothingReceived);
+    system_program::transfer(
+        CpiContext::new(
+            system_program,
+            system_program::Transfer {
+                from,
+                to: sol_vault,
+            },
+        ),
+        amount,
+    )?;
+    Ok(amount)
+}
+
+/// 从SOL托管账户向接收者支付lamports
+/// 托管账户由本程序拥有，可以直接扣减余额，无需CPI
+pub fn pay_lamports<'info>(
+    sol_vault: &Account<'info, SolVault>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    sol_vault.sub_lamports(amount)?;
+    to.add_lamports(amount)?;
+    Ok(())
+}
+
+/// 铸造下注NFT所需的账户
+pub struct BetNft<'info> {
+    /// 下注NFT铸造器
+    pub mint: AccountInfo<'info>,
+    /// 接收NFT的代币账户
+    pub token: AccountInfo<'info>,
+    /// 元数据账户
+    pub metadata: AccountInfo<'info>,
+    /// 主版本账户
+    pub master_edition: AccountInfo<'info>,
+    /// 赛事账户，作为铸币权限和元数据更新权限
+    pub tournament: AccountInfo<'info>,
+    /// 支付账户
+    pub payer: AccountInfo<'info>,
+    /// 代币程序
+    pub token_program: AccountInfo<'info>,
+    /// Metaplex元数据程序
+    pub token_metadata_program: AccountInfo<'info>,
+    /// 系统程序
+    pub system_program: AccountInfo<'info>,
+    /// 租金系统变量
+    pub rent: AccountInfo<'info>,
+}
+
+impl<'info> BetNft<'info> {
+    /// 铸造唯一的一枚NFT，并创建元数据和供应量为0的主版本
+    /// 元数据名称和uri都由链上的赛事、下注对象和金额生成，下注者无法伪造
+    pub fn mint(
+        &self,
+        signer: &[&[&[u8]]],
+        target_name: &str,
+        target: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let name = Self::metadata_name(target_name, amount);
+        let uri = format!("{}{}/{}/{}", BET_NFT_URI_PREFIX, self.tournament.key, target, amount);
+        
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                MintTo {
+                    mint: self.mint.clone(),
+                    to: self.token.clone(),
+                    authority: self.tournament.clone(),
+                },
+                signer,
+            ),
+            1,
+        )?;
+        
+        metadata::create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.clone(),
+                CreateMetadataAccountsV3 {
+                    metadata: self.metadata.clone(),
+                    mint: self.mint.clone(),
+                    mint_authority: self.tournament.clone(),
+                    payer: self.payer.clone(),
+                    update_authority: self.tournament.clone(),
+                    system_program: self.system_program.clone(),
+                    rent: self.rent.clone(),
+                },
+                signer,
+            ),
+            DataV2 {
+                name,
+                symbol: BET_NFT_SYMBOL.to_string(),
+                uri,
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            true,
+            true,
+            None,
+        )?;
+        
+        metadata::create_master_edition_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.clone(),
+                CreateMasterEditionV3 {
+                    edition: self.master_edition.clone(),
+                    mint: self.mint.clone(),
+                    update_authority: self.tournament.clone(),
+                    mint_authority: self.tournament.clone(),
+                    payer: self.payer.clone(),
+                    metadata: self.metadata.clone(),
+                    token_program: self.token_program.clone(),
+                    system_program: self.system_program.clone(),
+                    rent: self.rent.clone(),
+                },
+                signer,
+            ),
+            Some(0),
+        )
+    }
+    
+    /// 元数据名称为"下注对象 金额"，超出Metaplex名称长度时按字符边界截断下注对象
+    pub fn metadata_name(target_name: &str, amount: u64) -> String {
+        let suffix = format!(" {}", amount);
+        let max_target_len = BET_NFT_MAX_NAME_LEN.saturating_sub(suffix.len());
+        let mut end = target_name.len().min(max_target_len);
+        while !target_name.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}{}", &target_name[..end], suffix)
+    }
+}
+
+/// 初始化指令所需的账户结构
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    /// 合约管理员，支付初始化费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 状态账户，使用PDA地址
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + State::LEN,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// wanzi代币铸造器
+    pub wanzi_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// matchp代币铸造器
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
     /// vote代币铸造器
-    pub vote_mint: Box<Account<'info, Mint>>,
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 创建赛事指令所需的账户结构
+#[derive(Accounts)]
+pub struct CreateTournament<'info> {
+    /// 赛事管理员，支付创建费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，使用PDA地址
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Tournament::LEN,
+        seeds = [b"tournament", authority.key().as_ref()],
+        bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 状态账户，用于检查全局暂停和创建权限，校验matchp和下注代币
+    #[account(
+        seeds = [b"state"],
+        bump,
+        constraint = !state.paused @ TournamentError::ProtocolPaused
+    )]
+    pub state: Box<Account<'info, State>>,
+    
+    /// 赛事运营角色，白名单创建模式下非合约管理员必须提供
+    #[account(
+        seeds = [b"role", [Role::Operator as u8].as_ref(), authority.key().as_ref()],
+        bump = operator_role.bump
+    )]
+    pub operator_role: Option<Account<'info, RoleAssignment>>,
+    
+    /// 赛事的下注代币铸造器，wanzi或白名单中的代币
+    #[account(mint::token_program = currency_token_program)]
+    pub currency_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// 下注代币白名单，选择wanzi以外的代币时必须提供
+    #[account(
+        seeds = [b"currency_allowlist"],
+        bump = currency_allowlist.bump
+    )]
+    pub currency_allowlist: Option<Box<Account<'info, CurrencyAllowlist>>>,
+    
+    /// matchp代币铸造器
+    #[account(address = state.matchp_mint @ TournamentError::InvalidMatchpMint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// 赛事专属的vote代币铸造器，由赛事PDA铸造，精度与matchp一致
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = matchp_mint.decimals,
+        mint::authority = tournament,
+        seeds = [b"vote_mint", tournament.key().as_ref()],
+        bump
+    )]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 管理员的matchp代币账户
+    #[account(
+        mut,
+        constraint = authority_matchp_token.mint == matchp_mint.key() @ TournamentError::InvalidTokenMint,
+        constraint = authority_matchp_token.owner == authority.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub authority_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 管理员的vote代币账户
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = vote_mint,
+        associated_token::authority = authority
+    )]
+    pub authority_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的matchp代币账户
+    #[account(
+        init,
+        payer = authority,
+        token::mint = matchp_mint,
+        token::authority = tournament,
+        seeds = [b"tournament_matchp", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的vote代币账户
+    #[account(
+        init,
+        payer = authority,
+        token::mint = vote_mint,
+        token::authority = tournament,
+        seeds = [b"tournament_vote", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事方的质押份额记录
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StakePosition::LEN,
+        seeds = [b"stake_position", tournament.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 下注代币的代币程序，可以与matchp和vote使用的代币程序不同
+    pub currency_token_program: Interface<'info, TokenInterface>,
+    /// 关联代币程序
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 创建轮次指令所需的账户结构
+#[derive(Accounts)]
+#[instruction(name: String, round_number: u8)]
+pub struct CreateTournamentRound<'info> {
+    /// 赛事管理员，支付创建费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        mut,
+        constraint = tournament.authority == authority.key(),
+        constraint = tournament.is_active
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 轮次账户，使用PDA地址
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TournamentRound::LEN,
+        seeds = [b"round", tournament.key().as_ref(), &[round_number]],
+        bump
+    )]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 创建团队指令所需的账户结构
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateTeam<'info> {
+    /// 赛事管理员，支付创建费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        mut,
+        constraint = tournament.authority == authority.key(),
+        constraint = tournament.is_active
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 轮次账户，验证轮次属于该赛事
+    #[account(
+        constraint = round.tournament == tournament.key(),
+        constraint = round.is_active
+    )]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 团队账户，使用PDA地址
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Team::LEN,
+        seeds = [b"team", round.key().as_ref(), name.as_bytes()],
+        bump
+    )]
+    pub team: Account<'info, Team>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 下注指令所需的账户结构
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct PlaceBet<'info> {
+    /// 下注用户，支付下注费用
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// 赛事账户，验证赛事是否激活，下注后锁定手续费
+    #[account(
+        mut,
+        constraint = tournament.is_active,
+        constraint = !tournament.paused @ TournamentError::TournamentPaused
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 状态账户，检查全局暂停
+    #[account(
+        seeds = [b"state"],
+        bump,
+        constraint = !state.paused @ TournamentError::ProtocolPaused
+    )]
+    pub state: Box<Account<'info, State>>,
+    
+    /// 轮次账户，验证轮次是否激活
+    #[account(
+        mut,
+        constraint = round.tournament == tournament.key(),
+        constraint = round.is_active
+    )]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 团队账户，验证团队是否属于该轮次
+    #[account(
+        constraint = team.tournament == tournament.key(),
+        constraint = team.round == round.key()
+    )]
+    pub team: Account<'info, Team>,
+    
+    /// 下注账户
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Bet::LEN
+    )]
+    pub bet: Account<'info, Bet>,
+    
+    /// 代表该下注的NFT铸造器，由赛事PDA控制
+    #[account(
+        init,
+        payer = user,
+        mint::decimals = 0,
+        mint::authority = tournament,
+        mint::freeze_authority = tournament,
+        mint::token_program = nft_token_program,
+        seeds = [b"bet_mint", bet.key().as_ref()],
+        bump
+    )]
+    pub bet_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 用户接收下注NFT的关联代币账户
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = bet_mint,
+        associated_token::authority = user,
+        associated_token::token_program = nft_token_program
+    )]
+    pub user_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 下注NFT的元数据账户
+    /// CHECK: 由Metaplex元数据程序创建和校验
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), bet_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub bet_metadata: UncheckedAccount<'info>,
+    
+    /// 下注NFT的主版本账户
+    /// CHECK: 由Metaplex元数据程序创建和校验
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), bet_mint.key().as_ref(), b"edition"],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub bet_master_edition: UncheckedAccount<'info>,
+    
+    /// 用户的下注代币账户，SOL赛事无需提供
+    #[account(
+        mut,
+        constraint = user_currency_token.mint == tournament.currency_mint @ TournamentError::InvalidTokenMint,
+        constraint = user_currency_token.owner == user.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub user_currency_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// 用户的vote代币账户
+    #[account(
+        mut,
+        constraint = user_vote_token.mint == tournament.vote_mint @ TournamentError::InvalidTokenMint,
+        constraint = user_vote_token.owner == user.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub user_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的下注代币托管账户，SOL赛事无需提供
+    #[account(
+        init_if_needed,
+        payer = user,
+        token::mint = currency_mint,
+        token::authority = tournament,
+        token::token_program = currency_token_program,
+        seeds = [b"currency_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub currency_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    
+    /// SOL赛事的下注托管账户，由本程序拥有
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + SolVault::LEN,
+        seeds = [b"sol_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: Option<Box<Account<'info, SolVault>>>,
+    
+    /// 赛事的vote代币账户
+    #[account(
+        init_if_needed,
+        payer = user,
+        token::mint = vote_mint,
+        token::authority = tournament,
+        seeds = [b"tournament_vote", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的下注代币铸造器，SOL赛事无需提供
+    #[account(
+        address = tournament.currency_mint @ TournamentError::InvalidCurrencyMint,
+        mint::token_program = currency_token_program
+    )]
+    pub currency_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 下注代币的代币程序，可以与matchp和vote使用的代币程序不同
+    pub currency_token_program: Interface<'info, TokenInterface>,
+    /// 下注NFT使用的SPL Token程序
+    pub nft_token_program: Program<'info, Token>,
+    /// 关联代币程序
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// Metaplex元数据程序
+    pub token_metadata_program: Program<'info, Metadata>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 完成轮次指令所需的账户结构
+#[derive(Accounts)]
+pub struct CompleteRound<'info> {
+    /// 赛事管理员，必须是签名者
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        mut,
+        constraint = tournament.authority == authority.key(),
+        constraint = tournament.is_active
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 轮次账户，验证轮次是否激活
+    #[account(
+        mut,
+        constraint = round.tournament == tournament.key(),
+        constraint = round.is_active
+    )]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 获胜团队账户，验证团队是否属于该轮次
+    #[account(
+        mut,
+        constraint = winner_team.tournament == tournament.key(),
+        constraint = winner_team.round == round.key()
+    )]
+    pub winner_team: Account<'info, Team>,
+    
+    /// 预言机结果账户，赛事配置了预言机时必须提供
+    /// CHECK: 在指令中校验所有者和数据布局
+    pub oracle_result: Option<UncheckedAccount<'info>>,
+}
+
+/// 配置结果预言机指令所需的账户结构
+#[derive(Accounts)]
+pub struct ConfigureOracle<'info> {
+    /// 赛事管理员，必须是签名者
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        mut,
+        constraint = tournament.authority == authority.key(),
+        constraint = tournament.is_active
+    )]
+    pub tournament: Account<'info, Tournament>,
+}
+
+/// 结算下注指令所需的账户结构
+#[derive(Accounts)]
+pub struct SettleBet<'info> {
+    /// 赛事账户，记录手续费奖励
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 轮次账户，验证轮次是否已完成
+    #[account(
+        constraint = round.tournament == tournament.key(),
+        constraint = round.is_completed
+    )]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 获胜团队账户，验证团队是否获胜
+    #[account(
+        constraint = winner_team.tournament == tournament.key(),
+        constraint = winner_team.round == round.key(),
+        constraint = winner_team.is_winner
+    )]
+    pub winner_team: Account<'info, Team>,
+    
+    /// 下注账户，验证下注是否未结算
+    #[account(
+        mut,
+        constraint = bet.tournament == tournament.key(),
+        constraint = bet.round == round.key(),
+        constraint = !bet.is_settled
+    )]
+    pub bet: Account<'info, Bet>,
+    
+    /// 下注NFT的持有者，必须是签名者
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// 持有者的下注NFT代币账户，验证持有该下注的NFT
+    #[account(
+        mut,
+        constraint = user_bet_nft.mint == bet.mint,
+        constraint = user_bet_nft.owner == user.key() @ TournamentError::NotBetOwner,
+        constraint = user_bet_nft.amount == 1 @ TournamentError::NotBetOwner
+    )]
+    pub user_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 下注NFT铸造器
+    #[account(
+        mut,
+        address = bet.mint
+    )]
+    pub bet_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 下注NFT的元数据账户
+    /// CHECK: 由Metaplex元数据程序校验
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), bet_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub bet_metadata: UncheckedAccount<'info>,
+    
+    /// 下注NFT的主版本账户
+    /// CHECK: 由Metaplex元数据程序校验
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), bet_mint.key().as_ref(), b"edition"],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub bet_master_edition: UncheckedAccount<'info>,
+    
+    /// 用户的下注代币账户，SOL赛事无需提供
+    #[account(
+        mut,
+        constraint = user_currency_token.mint == tournament.currency_mint @ TournamentError::InvalidTokenMint,
+        constraint = user_currency_token.owner == user.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub user_currency_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    
+    /// 用户的vote代币账户
+    #[account(
+        mut,
+        constraint = user_vote_token.mint == tournament.vote_mint @ TournamentError::InvalidTokenMint,
+        constraint = user_vote_token.owner == user.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub user_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的下注代币托管账户，SOL赛事无需提供
+    #[account(
+        mut,
+        token::token_program = currency_token_program,
+        seeds = [b"currency_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub currency_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// SOL赛事的下注托管账户
+    #[account(
+        mut,
+        seeds = [b"sol_vault", tournament.key().as_ref()],
+        bump = sol_vault.bump
+    )]
+    pub sol_vault: Option<Box<Account<'info, SolVault>>>,
+    
+    /// 赛事的vote代币账户
+    #[account(
+        mut,
+        seeds = [b"tournament_vote", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 质押奖励池，收取手续费时必须提供
+    #[account(
+        mut,
+        seeds = [b"rewards_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub rewards_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    
+    /// 赛事的下注代币铸造器，SOL赛事无需提供
+    #[account(
+        address = tournament.currency_mint @ TournamentError::InvalidCurrencyMint,
+        mint::token_program = currency_token_program
+    )]
+    pub currency_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 下注代币的代币程序，可以与matchp和vote使用的代币程序不同
+    pub currency_token_program: Interface<'info, TokenInterface>,
+    /// 下注NFT使用的SPL Token程序
+    pub nft_token_program: Program<'info, Token>,
+    /// Metaplex元数据程序
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+/// 关闭赛事指令所需的账户结构
+#[derive(Accounts)]
+pub struct CloseTournament<'info> {
+    /// 赛事管理员，必须是签名者
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        mut,
+        has_one = authority,
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 状态账户，用于校验matchp代币
+    #[account(
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Box<Account<'info, State>>,
+    
+    /// 赛事的matchp代币账户
+    #[account(
+        mut,
+        seeds = [b"tournament_matchp", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// 管理员的matchp代币账户
+    #[account(
+        mut,
+        constraint = authority_matchp_token.mint == matchp_mint.key() @ TournamentError::InvalidTokenMint,
+        constraint = authority_matchp_token.owner == authority.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub authority_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的vote代币账户
+    #[account(
+        mut,
+        seeds = [b"tournament_vote", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// 赛事的下注代币托管账户，SOL赛事无需提供
+    #[account(
+        mut,
+        token::token_program = currency_token_program,
+        seeds = [b"currency_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub currency_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// SOL赛事的下注托管账户
+    #[account(
+        mut,
+        seeds = [b"sol_vault", tournament.key().as_ref()],
+        bump = sol_vault.bump
+    )]
+    pub sol_vault: Option<Box<Account<'info, SolVault>>>,
+    
+    /// 管理员的下注代币账户，SOL赛事无需提供
+    #[account(
+        mut,
+        constraint = authority_currency_token.mint == tournament.currency_mint @ TournamentError::InvalidTokenMint,
+        constraint = authority_currency_token.owner == authority.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub authority_currency_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    
+    /// 赛事专属的vote代币铸造器
+    #[account(
+        mut,
+        address = tournament.vote_mint @ TournamentError::InvalidVoteMint
+    )]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 赛事方的质押份额记录
+    #[account(
+        mut,
+        seeds = [b"stake_position", tournament.key().as_ref(), authority.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+    
+    /// 赛事的下注代币铸造器，SOL赛事无需提供
+    #[account(
+        address = tournament.currency_mint @ TournamentError::InvalidCurrencyMint,
+        mint::token_program = currency_token_program
+    )]
+    pub currency_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    /// matchp代币铸造器
+    #[account(address = state.matchp_mint @ TournamentError::InvalidMatchpMint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 下注代币的代币程序，可以与matchp和vote使用的代币程序不同
+    pub currency_token_program: Interface<'info, TokenInterface>,
+}
+
+/// 更新代币水龙头地址指令所需的账户结构
+#[derive(Accounts)]
+pub struct UpdateTokenFaucet<'info> {
+    /// 合约管理员，必须是签名者
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 状态账户，验证调用者是合约管理员或全局管理员
+    #[account(
+        mut,
+        constraint = state.authority == authority.key() || admin_role.is_some() @ TournamentError::MissingRole,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 全局管理员角色，合约管理员本人调用时无需提供
+    #[account(
+        seeds = [b"role", [Role::Admin as u8].as_ref(), authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleAssignment>>,
+}
+
+/// 提议转移管理员指令所需的账户结构
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    /// 当前合约管理员，必须是签名者
+    pub authority: Signer<'info>,
+    
+    /// 状态账户，验证调用者是管理员
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+}
+
+/// 接受管理员转移指令所需的账户结构
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    /// 被提议的新管理员，必须是签名者
+    pub pending_authority: Signer<'info>,
+    
+    /// 状态账户，验证调用者是被提议的新管理员
+    #[account(
+        mut,
+        constraint = state.pending_authority == pending_authority.key() @ TournamentError::NotPendingAuthority,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+}
+
+/// 取消管理员转移指令所需的账户结构
+#[derive(Accounts)]
+pub struct CancelAuthorityTransfer<'info> {
+    /// 当前合约管理员，必须是签名者
+    pub authority: Signer<'info>,
+    
+    /// 状态账户，验证调用者是管理员
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+}
+
+/// 设置全局暂停指令所需的账户结构
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    /// 合约管理员或暂停管理员
+    pub pauser: Signer<'info>,
+    
+    /// 状态账户，验证调用者是合约管理员或暂停管理员
+    #[account(
+        mut,
+        constraint = state.authority == pauser.key() || pauser_role.is_some() @ TournamentError::MissingRole,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 暂停管理员角色，合约管理员本人调用时无需提供
+    #[account(
+        seeds = [b"role", [Role::Pauser as u8].as_ref(), pauser.key().as_ref()],
+        bump = pauser_role.bump
+    )]
+    pub pauser_role: Option<Account<'info, RoleAssignment>>,
+}
+
+/// 设置赛事暂停指令所需的账户结构
+#[derive(Accounts)]
+pub struct SetTournamentPaused<'info> {
+    /// 合约管理员或暂停管理员
+    pub pauser: Signer<'info>,
+    
+    /// 状态账户，验证调用者是合约管理员或暂停管理员
+    #[account(
+        constraint = state.authority == pauser.key() || pauser_role.is_some() @ TournamentError::MissingRole,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 暂停管理员角色，合约管理员本人调用时无需提供
+    #[account(
+        seeds = [b"role", [Role::Pauser as u8].as_ref(), pauser.key().as_ref()],
+        bump = pauser_role.bump
+    )]
+    pub pauser_role: Option<Account<'info, RoleAssignment>>,
+    
+    /// 被暂停或恢复的赛事
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+}
+
+/// 配置赛事创建权限指令所需的账户结构
+#[derive(Accounts)]
+pub struct ConfigureTournamentCreation<'info> {
+    /// 合约管理员或全局管理员，必须是签名者
+    pub authority: Signer<'info>,
+    
+    /// 状态账户，验证调用者是合约管理员或全局管理员
+    #[account(
+        mut,
+        constraint = state.authority == authority.key() || admin_role.is_some() @ TournamentError::MissingRole,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 全局管理员角色，合约管理员本人调用时无需提供
+    #[account(
+        seeds = [b"role", [Role::Admin as u8].as_ref(), authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleAssignment>>,
+}
+
+/// 授予角色指令所需的账户结构
+#[derive(Accounts)]
+#[instruction(role: Role, holder: Pubkey)]
+pub struct GrantRole<'info> {
+    /// 合约管理员或全局管理员，支付角色账户费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 状态账户，验证调用者是合约管理员或全局管理员
+    #[account(
+        constraint = state.authority == authority.key() || admin_role.is_some() @ TournamentError::MissingRole,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 全局管理员角色，合约管理员本人调用时无需提供
+    #[account(
+        seeds = [b"role", [Role::Admin as u8].as_ref(), authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleAssignment>>,
+    
+    /// 被授予的角色账户，每个账户的每种角色只有一个
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RoleAssignment::LEN,
+        seeds = [b"role", [role as u8].as_ref(), holder.as_ref()],
+        bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+}
+
+/// 撤销角色指令所需的账户结构
+#[derive(Accounts)]
+#[instruction(role: Role, holder: Pubkey)]
+pub struct RevokeRole<'info> {
+    /// 合约管理员或全局管理员，接收角色账户的租金
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 状态账户，验证调用者是合约管理员或全局管理员
+    #[account(
+        constraint = state.authority == authority.key() || admin_role.is_some() @ TournamentError::MissingRole,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 全局管理员角色，合约管理员本人调用时无需提供
+    #[account(
+        seeds = [b"role", [Role::Admin as u8].as_ref(), authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleAssignment>>,
+    
+    /// 被撤销的角色账户，撤销后关闭
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"role", [role as u8].as_ref(), holder.as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+}
+
+/// 添加下注代币指令所需的账户结构
+#[derive(Accounts)]
+pub struct AddCurrency<'info> {
+    /// 合约管理员，支付白名单账户费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 状态账户，验证调用者是合约管理员或全局管理员
+    #[account(
+        constraint = state.authority == authority.key() || admin_role.is_some() @ TournamentError::MissingRole,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 全局管理员角色，合约管理员本人调用时无需提供
+    #[account(
+        seeds = [b"role", [Role::Admin as u8].as_ref(), authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleAssignment>>,
+    
+    /// 下注代币白名单，首次添加时创建
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + CurrencyAllowlist::LEN,
+        seeds = [b"currency_allowlist"],
+        bump
+    )]
+    pub currency_allowlist: Account<'info, CurrencyAllowlist>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+}
+
+/// 移除下注代币指令所需的账户结构
+#[derive(Accounts)]
+pub struct RemoveCurrency<'info> {
+    /// 合约管理员，必须是签名者
+    pub authority: Signer<'info>,
+    
+    /// 状态账户，验证调用者是合约管理员或全局管理员
+    #[account(
+        constraint = state.authority == authority.key() || admin_role.is_some() @ TournamentError::MissingRole,
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 全局管理员角色，合约管理员本人调用时无需提供
+    #[account(
+        seeds = [b"role", [Role::Admin as u8].as_ref(), authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleAssignment>>,
+    
+    /// 下注代币白名单
+    #[account(
+        mut,
+        seeds = [b"currency_allowlist"],
+        bump = currency_allowlist.bump
+    )]
+    pub currency_allowlist: Account<'info, CurrencyAllowlist>,
+}
+
+/// 质押赛事指令所需的账户结构
+#[derive(Accounts)]
+pub struct StakeTournament<'info> {
+    /// 赛事管理员，支付质押费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        mut,
+        constraint = tournament.authority == authority.key(),
+        constraint = tournament.is_active,
+        constraint = !tournament.paused @ TournamentError::TournamentPaused,
+        seeds = [b"tournament", authority.key().as_ref()],
+        bump = tournament.bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 状态账户，用于检查全局暂停和校验matchp代币
+    #[account(
+        seeds = [b"state"],
+        bump,
+        constraint = !state.paused @ TournamentError::ProtocolPaused
+    )]
+    pub state: Box<Account<'info, State>>,
+    
+    /// 管理员的matchp代币账户
+    #[account(
+        mut,
+        constraint = authority_matchp_token.mint == matchp_mint.key() @ TournamentError::InvalidTokenMint,
+        constraint = authority_matchp_token.owner == authority.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub authority_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 管理员的vote代币账户
+    #[account(
+        mut,
+        constraint = authority_vote_token.mint == tournament.vote_mint @ TournamentError::InvalidTokenMint,
+        constraint = authority_vote_token.owner == authority.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub authority_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的matchp代币账户
+    #[account(
+        mut,
+        constraint = tournament_matchp_token.mint == matchp_mint.key(),
+        seeds = [b"tournament_matchp", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// matchp代币铸造器
+    #[account(address = state.matchp_mint @ TournamentError::InvalidMatchpMint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// 赛事专属的vote代币铸造器
+    #[account(
+        mut,
+        address = tournament.vote_mint @ TournamentError::InvalidVoteMint
+    )]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 赛事方的质押份额记录
+    #[account(
+        mut,
+        seeds = [b"stake_position", tournament.key().as_ref(), authority.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 赎回质押指令所需的账户结构
+#[derive(Accounts)]
+pub struct UnstakeTournament<'info> {
+    /// 赛事管理员，必须是签名者
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"tournament", authority.key().as_ref()],
+        bump = tournament.bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 状态账户，用于校验matchp代币
+    #[account(
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Box<Account<'info, State>>,
+    
+    /// 管理员的vote代币账户，赎回的vote从这里销毁
+    #[account(
+        mut,
+        constraint = authority_vote_token.mint == vote_mint.key() @ TournamentError::InvalidTokenAccount,
+        constraint = authority_vote_token.owner == authority.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub authority_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 管理员接收matchp的代币账户
+    #[account(
+        mut,
+        constraint = authority_matchp_token.mint == tournament_matchp_token.mint @ TournamentError::InvalidTokenAccount,
+        constraint = authority_matchp_token.owner == authority.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub authority_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的matchp代币账户
+    #[account(
+        mut,
+        seeds = [b"tournament_matchp", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事专属的vote代币铸造器
+    #[account(
+        mut,
+        address = tournament.vote_mint @ TournamentError::InvalidVoteMint
+    )]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 赛事方的质押份额记录
+    #[account(
+        mut,
+        seeds = [b"stake_position", tournament.key().as_ref(), authority.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+    
+    /// matchp代币铸造器
+    #[account(address = state.matchp_mint @ TournamentError::InvalidMatchpMint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 委托质押指令所需的账户结构
+#[derive(Accounts)]
+pub struct DelegateStake<'info> {
+    /// 委托者，支付账户创建费用
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+    
+    /// 状态账户，用于检查全局暂停和校验matchp代币
+    #[account(
+        seeds = [b"state"],
+        bump,
+        constraint = !state.paused @ TournamentError::ProtocolPaused
+    )]
+    pub state: Box<Account<'info, State>>,
+    
+    /// 赛事账户，赛事方不能作为委托者
+    #[account(
+        mut,
+        constraint = tournament.is_active @ TournamentError::TournamentNotActive,
+        constraint = !tournament.paused @ TournamentError::TournamentPaused,
+        constraint = tournament.authority != delegator.key() @ TournamentError::InvalidDelegator
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    
+    /// 委托者的份额记录
+    #[account(
+        init_if_needed,
+        payer = delegator,
+        space = 8 + StakePosition::LEN,
+        seeds = [b"stake_position", tournament.key().as_ref(), delegator.key().as_ref()],
+        bump
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+    
+    /// 委托者的matchp代币账户
+    #[account(
+        mut,
+        constraint = delegator_matchp_token.mint == state.matchp_mint @ TournamentError::InvalidTokenAccount,
+        constraint = delegator_matchp_token.owner == delegator.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub delegator_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的matchp质押池
+    #[account(
+        mut,
+        seeds = [b"tournament_matchp", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的质押凭证铸造器，精度与matchp一致，赛事持有冻结权限使凭证不能转让
+    #[account(
+        init_if_needed,
+        payer = delegator,
+        mint::decimals = matchp_mint.decimals,
+        mint::authority = tournament,
+        mint::freeze_authority = tournament,
+        seeds = [b"stake_receipt", tournament.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 委托者的质押凭证账户
+    #[account(
+        init_if_needed,
+        payer = delegator,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = delegator
+    )]
+    pub delegator_receipt_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// matchp代币铸造器
+    #[account(address = state.matchp_mint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 关联代币程序
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 取消委托指令所需的账户结构
+#[derive(Accounts)]
+pub struct UndelegateStake<'info> {
+    /// 委托者
+    pub delegator: Signer<'info>,
+    
+    /// 赛事账户
+    #[account(mut)]
+    pub tournament: Box<Account<'info, Tournament>>,
+    
+    /// 状态账户，用于校验matchp代币
+    #[account(
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Box<Account<'info, State>>,
+    
+    /// 委托者的份额记录
+    #[account(
+        mut,
+        has_one = tournament,
+        constraint = stake_position.owner == delegator.key() @ TournamentError::InvalidDelegator,
+        seeds = [b"stake_position", tournament.key().as_ref(), delegator.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+    
+    /// 委托者接收matchp的代币账户
+    #[account(
+        mut,
+        constraint = delegator_matchp_token.mint == tournament_matchp_token.mint @ TournamentError::InvalidTokenAccount,
+        constraint = delegator_matchp_token.owner == delegator.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub delegator_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的matchp质押池
+    #[account(
+        mut,
+        seeds = [b"tournament_matchp", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的质押凭证铸造器
+    #[account(
+        mut,
+        seeds = [b"stake_receipt", tournament.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 委托者的质押凭证账户
+    #[account(
+        mut,
+        constraint = delegator_receipt_token.mint == receipt_mint.key() @ TournamentError::InvalidTokenAccount,
+        constraint = delegator_receipt_token.owner == delegator.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub delegator_receipt_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// matchp代币铸造器
+    #[account(address = state.matchp_mint @ TournamentError::InvalidMatchpMint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 配置手续费指令所需的账户结构
+#[derive(Accounts)]
+pub struct ConfigureFees<'info> {
+    /// 赛事管理员，支付奖励池创建费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = tournament.is_active @ TournamentError::TournamentNotActive
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 质押奖励池
+    #[account(
+        init_if_needed,
+        payer = authority,
+        token::mint = vote_mint,
+        token::authority = tournament,
+        seeds = [b"rewards_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub rewards_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 领取质押奖励指令所需的账户结构
+#[derive(Accounts)]
+pub struct ClaimStakeRewards<'info> {
+    /// 质押者
+    pub owner: Signer<'info>,
+    
+    /// 赛事账户
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 质押者的份额记录
+    #[account(
+        mut,
+        has_one = tournament,
+        has_one = owner,
+        seeds = [b"stake_position", tournament.key().as_ref(), owner.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+    
+    /// 质押者接收奖励的vote代币账户
+    #[account(
+        mut,
+        constraint = owner_vote_token.mint == tournament.vote_mint @ TournamentError::InvalidTokenAccount,
+        constraint = owner_vote_token.owner == owner.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub owner_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 质押奖励池
+    #[account(
+        mut,
+        seeds = [b"rewards_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub rewards_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 配置vote分发指令所需的账户结构
+#[derive(Accounts)]
+pub struct ConfigureVoteDistribution<'info> {
+    /// 赛事管理员，支付创建费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        has_one = authority,
+        constraint = tournament.is_active @ TournamentError::TournamentNotActive
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// vote分发配置账户，使用PDA地址
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + VoteDistribution::LEN,
+        seeds = [b"vote_distribution", tournament.key().as_ref()],
+        bump
+    )]
+    pub vote_distribution: Account<'info, VoteDistribution>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+}
+
+/// 注入vote分发资金指令所需的账户结构
+#[derive(Accounts)]
+pub struct FundVoteDistribution<'info> {
+    /// 赛事管理员，支付托管账户创建费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        has_one = authority,
+        constraint = tournament.is_active @ TournamentError::TournamentNotActive
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// vote分发配置账户
+    #[account(
+        has_one = tournament,
+        seeds = [b"vote_distribution", tournament.key().as_ref()],
+        bump = vote_distribution.bump
+    )]
+    pub vote_distribution: Account<'info, VoteDistribution>,
+    
+    /// 管理员的vote代币账户
+    #[account(
+        mut,
+        constraint = authority_vote_token.mint == vote_mint.key() @ TournamentError::InvalidTokenAccount,
+        constraint = authority_vote_token.owner == authority.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub authority_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// vote分发托管账户
+    #[account(
+        init_if_needed,
+        payer = authority,
+        token::mint = vote_mint,
+        token::authority = tournament,
+        seeds = [b"vote_distribution_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub distribution_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 注册玩家指令所需的账户结构
+#[derive(Accounts)]
+#[instruction(player: Pubkey)]
+pub struct RegisterPlayer<'info> {
+    /// 赛事管理员，支付注册记录费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        has_one = authority,
+        constraint = tournament.is_active @ TournamentError::TournamentNotActive
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 玩家注册记录，每位玩家在每个赛事只能注册一次
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PlayerRegistration::LEN,
+        seeds = [b"player", tournament.key().as_ref(), player.as_ref()],
+        bump
+    )]
+    pub player_registration: Account<'info, PlayerRegistration>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+}
+
+/// 领取vote额度指令所需的账户结构
+#[derive(Accounts)]
+pub struct ClaimVoteAllowance<'info> {
+    /// 注册玩家
+    pub player: Signer<'info>,
+    
+    /// 赛事账户
+    #[account(
+        constraint = tournament.is_active @ TournamentError::TournamentNotActive
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// vote分发配置账户
+    #[account(
+        mut,
+        has_one = tournament,
+        seeds = [b"vote_distribution", tournament.key().as_ref()],
+        bump = vote_distribution.bump
+    )]
+    pub vote_distribution: Account<'info, VoteDistribution>,
+    
+    /// 玩家注册记录
+    #[account(
+        mut,
+        has_one = tournament,
+        has_one = player,
+        seeds = [b"player", tournament.key().as_ref(), player.key().as_ref()],
+        bump = player_registration.bump
+    )]
+    pub player_registration: Account<'info, PlayerRegistration>,
+    
+    /// 玩家接收vote的代币账户
+    #[account(
+        mut,
+        constraint = player_vote_token.mint == tournament.vote_mint @ TournamentError::InvalidTokenAccount,
+        constraint = player_vote_token.owner == player.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub player_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// vote分发托管账户
+    #[account(
+        mut,
+        seeds = [b"vote_distribution_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub distribution_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 用wanzi兑换vote指令所需的账户结构
+#[derive(Accounts)]
+pub struct SwapWanziForVote<'info> {
+    /// 兑换的玩家
+    pub player: Signer<'info>,
+    
+    /// 状态账户，用于校验wanzi代币
+    #[account(
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 赛事账户
+    #[account(
+        constraint = tournament.is_active @ TournamentError::TournamentNotActive
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// vote分发配置账户
+    #[account(
+        mut,
+        has_one = tournament,
+        seeds = [b"vote_distribution", tournament.key().as_ref()],
+        bump = vote_distribution.bump
+    )]
+    pub vote_distribution: Account<'info, VoteDistribution>,
+    
+    /// 玩家支付的wanzi代币账户
+    #[account(
+        mut,
+        constraint = player_wanzi_token.mint == state.wanzi_mint @ TournamentError::InvalidTokenAccount,
+        constraint = player_wanzi_token.owner == player.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub player_wanzi_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事管理员接收wanzi的代币账户
+    #[account(
+        mut,
+        constraint = authority_wanzi_token.mint == state.wanzi_mint @ TournamentError::InvalidTokenAccount,
+        constraint = authority_wanzi_token.owner == tournament.authority @ TournamentError::InvalidTokenAccount
+    )]
+    pub authority_wanzi_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 玩家接收vote的代币账户
+    #[account(
+        mut,
+        constraint = player_vote_token.mint == tournament.vote_mint @ TournamentError::InvalidTokenAccount,
+        constraint = player_vote_token.owner == player.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub player_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// vote分发托管账户
+    #[account(
+        mut,
+        seeds = [b"vote_distribution_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub distribution_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// wanzi代币铸造器
+    #[account(address = state.wanzi_mint @ TournamentError::InvalidWanziMint)]
+    pub wanzi_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 转让下注指令所需的账户结构
+#[derive(Accounts)]
+pub struct TransferBet<'info> {
+    /// 下注NFT的当前持有者，必须是签名者
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// 下注账户，验证下注未结算
+    #[account(
+        mut,
+        constraint = !bet.is_settled
+    )]
+    pub bet: Account<'info, Bet>,
+    
+    /// 持有者的下注NFT代币账户，验证调用者持有该下注的NFT
+    #[account(
+        mut,
+        constraint = user_bet_nft.mint == bet.mint,
+        constraint = user_bet_nft.owner == user.key() @ TournamentError::NotBetOwner,
+        constraint = user_bet_nft.amount == 1 @ TournamentError::NotBetOwner
+    )]
+    pub user_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 新的下注持有者
+    /// CHECK: 仅作为NFT关联代币账户的所有者
+    pub new_owner: UncheckedAccount<'info>,
+    
+    /// 新持有者接收下注NFT的关联代币账户
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = bet_mint,
+        associated_token::authority = new_owner,
+        associated_token::token_program = nft_token_program
+    )]
+    pub new_owner_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 下注NFT铸造器
+    #[account(address = bet.mint)]
+    pub bet_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 下注NFT使用的SPL Token程序
+    pub nft_token_program: Program<'info, Token>,
+    /// 关联代币程序
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// 挂单出售下注指令所需的账户结构
+#[derive(Accounts)]
+pub struct ListBet<'info> {
+    /// 卖家，即下注NFT的当前持有者
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    /// 下注账户，验证下注未结算
+    #[account(
+        constraint = !bet.is_settled
+    )]
+    pub bet: Account<'info, Bet>,
+    
+    /// 下注所属的轮次
+    #[account(address = bet.round)]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 卖家的下注NFT代币账户，验证卖家持有该下注的NFT
+    #[account(
+        mut,
+        constraint = seller_bet_nft.mint == bet.mint,
+        constraint = seller_bet_nft.owner == seller.key() @ TournamentError::NotBetOwner,
+        constraint = seller_bet_nft.amount == 1 @ TournamentError::NotBetOwner
+    )]
+    pub seller_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 下注NFT铸造器
+    #[account(address = bet.mint)]
+    pub bet_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 挂单账户，使用PDA地址
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Listing::LEN,
+        seeds = [b"listing", bet.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    /// 托管下注NFT的代币账户
+    #[account(
+        init,
+        payer = seller,
+        token::mint = bet_mint,
+        token::authority = listing,
+        token::token_program = nft_token_program,
+        seeds = [b"listing_escrow", bet.key().as_ref()],
+        bump
+    )]
+    pub escrow_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 下注NFT使用的SPL Token程序
+    pub nft_token_program: Program<'info, Token>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 撤销挂单指令所需的账户结构
+#[derive(Accounts)]
+pub struct CancelListing<'info> {
+    /// 卖家，必须是签名者
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    /// 挂单的下注账户
+    pub bet: Account<'info, Bet>,
+    
+    /// 挂单账户，验证调用者是卖家，撤单后关闭
+    #[account(
+        mut,
+        has_one = seller,
+        has_one = bet,
+        close = seller,
+        seeds = [b"listing", bet.key().as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    /// 托管下注NFT的代币账户
+    #[account(
+        mut,
+        token::token_program = nft_token_program,
+        seeds = [b"listing_escrow", bet.key().as_ref()],
+        bump
+    )]
+    pub escrow_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 卖家接收退还NFT的代币账户
+    #[account(
+        mut,
+        constraint = seller_bet_nft.mint == bet.mint,
+        constraint = seller_bet_nft.owner == seller.key()
+    )]
+    pub seller_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 下注NFT铸造器
+    #[account(address = bet.mint)]
+    pub bet_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 下注NFT使用的SPL Token程序
+    pub nft_token_program: Program<'info, Token>,
+}
+
+/// 购买挂单下注指令所需的账户结构
+#[derive(Accounts)]
+pub struct BuyBet<'info> {
+    /// 买家，支付标价
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    /// 状态账户，用于校验wanzi代币
+    #[account(
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 挂单的下注账户，验证下注未结算
+    #[account(
+        mut,
+        constraint = !bet.is_settled
+    )]
+    pub bet: Account<'info, Bet>,
+    
+    /// 下注所属的轮次
+    #[account(address = bet.round)]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 挂单账户，成交后关闭
+    #[account(
+        mut,
+        has_one = seller,
+        has_one = bet,
+        close = seller,
+        seeds = [b"listing", bet.key().as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    /// 卖家账户，接收挂单和托管账户的租金
+    /// CHECK: 通过listing的has_one约束校验
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+    
+    /// 买家的wanzi代币账户
+    #[account(
+        mut,
+        constraint = buyer_wanzi_token.mint == state.wanzi_mint @ TournamentError::InvalidTokenAccount,
+        constraint = buyer_wanzi_token.owner == buyer.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub buyer_wanzi_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 卖家的wanzi代币账户
+    #[account(
+        mut,
+        constraint = seller_wanzi_token.mint == state.wanzi_mint @ TournamentError::InvalidTokenAccount,
+        constraint = seller_wanzi_token.owner == seller.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub seller_wanzi_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 托管下注NFT的代币账户
+    #[account(
+        mut,
+        token::token_program = nft_token_program,
+        seeds = [b"listing_escrow", bet.key().as_ref()],
+        bump
+    )]
+    pub escrow_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 买家接收下注NFT的关联代币账户
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = bet_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = nft_token_program
+    )]
+    pub buyer_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 下注NFT铸造器
+    #[account(address = bet.mint)]
+    pub bet_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// wanzi代币铸造器
+    #[account(address = state.wanzi_mint @ TournamentError::InvalidWanziMint)]
+    pub wanzi_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 下注NFT使用的SPL Token程序
+    pub nft_token_program: Program<'info, Token>,
+    /// 关联代币程序
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// 创建数值市场指令所需的账户结构
+#[derive(Accounts)]
+#[instruction(market_index: u8)]
+pub struct CreateMarket<'info> {
+    /// 赛事管理员，支付创建费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        constraint = tournament.authority == authority.key(),
+        constraint = tournament.is_active
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 轮次账户，验证轮次属于该赛事
+    #[account(
+        constraint = round.tournament == tournament.key(),
+        constraint = round.is_active
+    )]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 市场账户，使用PDA地址
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Market::LEN,
+        seeds = [b"market", round.key().as_ref(), &[market_index]],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+    
+    /// 让分盘的热门方，仅让分盘需要
+    pub favorite_team: Option<Account<'info, Team>>,
+    /// 让分盘的冷门方，仅让分盘需要
+    pub underdog_team: Option<Account<'info, Team>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+}
+
+/// 数值市场下注指令所需的账户结构
+#[derive(Accounts)]
+#[instruction(amount: u64, outcome: u8)]
+pub struct PlaceMarketBet<'info> {
+    /// 下注用户，支付下注费用
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// 赛事账户，验证赛事是否激活，下注后锁定手续费
+    #[account(
+        mut,
+        constraint = tournament.is_active,
+        constraint = !tournament.paused @ TournamentError::TournamentPaused
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 状态账户，检查全局暂停
+    #[account(
+        seeds = [b"state"],
+        bump,
+        constraint = !state.paused @ TournamentError::ProtocolPaused
+    )]
+    pub state: Box<Account<'info, State>>,
+    
+    /// 轮次账户，验证轮次是否激活
+    #[account(
+        constraint = round.tournament == tournament.key(),
+        constraint = round.is_active
+    )]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 市场账户，验证市场属于该轮次
+    #[account(
+        mut,
+        constraint = market.tournament == tournament.key(),
+        constraint = market.round == round.key()
+    )]
+    pub market: Box<Account<'info, Market>>,
+    
+    /// 下注账户
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Bet::LEN
+    )]
+    pub bet: Box<Account<'info, Bet>>,
+    
+    /// 代表该下注的NFT铸造器，由赛事PDA控制
+    #[account(
+        init,
+        payer = user,
+        mint::decimals = 0,
+        mint::authority = tournament,
+        mint::freeze_authority = tournament,
+        mint::token_program = nft_token_program,
+        seeds = [b"bet_mint", bet.key().as_ref()],
+        bump
+    )]
+    pub bet_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 用户接收下注NFT的关联代币账户
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = bet_mint,
+        associated_token::authority = user,
+        associated_token::token_program = nft_token_program
+    )]
+    pub user_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 下注NFT的元数据账户
+    /// CHECK: 由Metaplex元数据程序创建和校验
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), bet_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub bet_metadata: UncheckedAccount<'info>,
+    
+    /// 下注NFT的主版本账户
+    /// CHECK: 由Metaplex元数据程序创建和校验
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), bet_mint.key().as_ref(), b"edition"],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub bet_master_edition: UncheckedAccount<'info>,
+    
+    /// 用户的下注代币账户，SOL赛事无需提供
+    #[account(
+        mut,
+        constraint = user_currency_token.mint == tournament.currency_mint @ TournamentError::InvalidTokenMint,
+        constraint = user_currency_token.owner == user.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub user_currency_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// 用户的vote代币账户
+    #[account(
+        mut,
+        constraint = user_vote_token.mint == tournament.vote_mint @ TournamentError::InvalidTokenMint,
+        constraint = user_vote_token.owner == user.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub user_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的下注代币托管账户，SOL赛事无需提供
+    #[account(
+        init_if_needed,
+        payer = user,
+        token::mint = currency_mint,
+        token::authority = tournament,
+        token::token_program = currency_token_program,
+        seeds = [b"currency_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub currency_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    
+    /// SOL赛事的下注托管账户，由本程序拥有
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + SolVault::LEN,
+        seeds = [b"sol_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: Option<Box<Account<'info, SolVault>>>,
+    
+    /// 赛事的vote代币账户
+    #[account(
+        init_if_needed,
+        payer = user,
+        token::mint = vote_mint,
+        token::authority = tournament,
+        seeds = [b"tournament_vote", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的下注代币铸造器，SOL赛事无需提供
+    #[account(
+        address = tournament.currency_mint @ TournamentError::InvalidCurrencyMint,
+        mint::token_program = currency_token_program
+    )]
+    pub currency_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 下注代币的代币程序，可以与matchp和vote使用的代币程序不同
+    pub currency_token_program: Interface<'info, TokenInterface>,
+    /// 下注NFT使用的SPL Token程序
+    pub nft_token_program: Program<'info, Token>,
+    /// 关联代币程序
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// Metaplex元数据程序
+    pub token_metadata_program: Program<'info, Metadata>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 报告比分指令所需的账户结构
+#[derive(Accounts)]
+pub struct ReportScore<'info> {
+    /// 赛事管理员，必须是签名者
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        constraint = tournament.authority == authority.key()
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 市场账户，验证市场属于该赛事
+    #[account(
+        mut,
+        constraint = market.tournament == tournament.key()
+    )]
+    pub market: Account<'info, Market>,
+    
+    /// 市场所属的轮次
+    #[account(address = market.round)]
+    pub round: Account<'info, TournamentRound>,
+}
+
+/// 报告队伍比分指令所需的账户结构
+#[derive(Accounts)]
+pub struct ReportTeamScore<'info> {
+    /// 赛事管理员，必须是签名者
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        constraint = tournament.authority == authority.key()
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 团队账户，验证团队属于该赛事
+    #[account(
+        mut,
+        constraint = team.tournament == tournament.key()
+    )]
+    pub team: Account<'info, Team>,
+    
+    /// 团队所属的轮次
+    #[account(address = team.round)]
+    pub round: Account<'info, TournamentRound>,
+}
+
+/// 结算让分盘指令所需的账户结构
+#[derive(Accounts)]
+pub struct ResolveHandicapMarket<'info> {
+    /// 赛事管理员，必须是签名者
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        constraint = tournament.authority == authority.key()
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 让分盘市场账户
+    #[account(
+        mut,
+        constraint = market.tournament == tournament.key()
+    )]
+    pub market: Account<'info, Market>,
+    
+    /// 热门方团队账户
+    #[account(address = market.favorite_team)]
+    pub favorite_team: Account<'info, Team>,
+    
+    /// 冷门方团队账户
+    #[account(address = market.underdog_team)]
+    pub underdog_team: Account<'info, Team>,
+}
+
+/// 配置结果裁定人指令所需的账户结构
+#[derive(Accounts)]
+pub struct ConfigureResolvers<'info> {
+    /// 赛事管理员，支付创建费用
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        mut,
+        constraint = tournament.authority == authority.key(),
+        constraint = tournament.is_active
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 裁定人集合账户，使用PDA地址
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ResolverSet::LEN,
+        seeds = [b"resolver_set", tournament.key().as_ref()],
+        bump
+    )]
+    pub resolver_set: Account<'info, ResolverSet>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+}
+
+/// 裁定人质押指令所需的账户结构
+#[derive(Accounts)]
+pub struct StakeResolver<'info> {
+    /// 裁定人，支付质押记录费用
+    #[account(mut)]
+    pub resolver: Signer<'info>,
+    
+    /// 状态账户，用于校验matchp代币
+    #[account(
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 赛事账户
+    #[account(
+        constraint = tournament.is_active
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 赛事的裁定人集合
+    #[account(
+        seeds = [b"resolver_set", tournament.key().as_ref()],
+        bump = resolver_set.bump
+    )]
+    pub resolver_set: Account<'info, ResolverSet>,
+    
+    /// 裁定人质押记录，使用PDA地址
+    #[account(
+        init_if_needed,
+        payer = resolver,
+        space = 8 + ResolverStake::LEN,
+        seeds = [b"resolver_stake", tournament.key().as_ref(), resolver.key().as_ref()],
+        bump
+    )]
+    pub resolver_stake: Account<'info, ResolverStake>,
+    
+    /// 裁定人的matchp代币账户
+    #[account(
+        mut,
+        constraint = resolver_matchp_token.mint == state.matchp_mint @ TournamentError::InvalidTokenAccount,
+        constraint = resolver_matchp_token.owner == resolver.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub resolver_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的裁定人质押托管账户
+    #[account(
+        init_if_needed,
+        payer = resolver,
+        token::mint = matchp_mint,
+        token::authority = tournament,
+        seeds = [b"resolver_stake_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub resolver_stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// matchp代币铸造器
+    #[account(address = state.matchp_mint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 取回裁定人质押指令所需的账户结构
+#[derive(Accounts)]
+pub struct UnstakeResolver<'info> {
+    /// 裁定人
+    pub resolver: Signer<'info>,
+    
+    /// 赛事账户
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 状态账户，用于校验matchp代币
+    #[account(
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Box<Account<'info, State>>,
+    
+    /// 裁定人质押记录
+    #[account(
+        mut,
+        has_one = tournament,
+        has_one = resolver,
+        seeds = [b"resolver_stake", tournament.key().as_ref(), resolver.key().as_ref()],
+        bump = resolver_stake.bump
+    )]
+    pub resolver_stake: Account<'info, ResolverStake>,
+    
+    /// 裁定人接收matchp的代币账户
+    #[account(
+        mut,
+        constraint = resolver_matchp_token.mint == resolver_stake_vault.mint @ TournamentError::InvalidTokenAccount,
+        constraint = resolver_matchp_token.owner == resolver.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub resolver_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的裁定人质押托管账户
+    #[account(
+        mut,
+        seeds = [b"resolver_stake_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub resolver_stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// matchp代币铸造器
+    #[account(address = state.matchp_mint @ TournamentError::InvalidMatchpMint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 提交结果承诺指令所需的账户结构
+#[derive(Accounts)]
+pub struct CommitResult<'info> {
+    /// 裁定人，支付承诺记录费用
+    #[account(mut)]
+    pub resolver: Signer<'info>,
+    
+    /// 赛事账户
+    #[account(
+        constraint = tournament.is_active
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 赛事的裁定人集合
+    #[account(
+        seeds = [b"resolver_set", tournament.key().as_ref()],
+        bump = resolver_set.bump
+    )]
+    pub resolver_set: Account<'info, ResolverSet>,
+    
+    /// 裁定人质押记录
+    #[account(
+        mut,
+        seeds = [b"resolver_stake", tournament.key().as_ref(), resolver.key().as_ref()],
+        bump = resolver_stake.bump
+    )]
+    pub resolver_stake: Account<'info, ResolverStake>,
+    
+    /// 轮次账户，验证轮次是否激活
+    #[account(
+        constraint = round.tournament == tournament.key(),
+        constraint = round.is_active
+    )]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 轮次的承诺记录，使用PDA地址
+    #[account(
+        init_if_needed,
+        payer = resolver,
+        space = 8 + RoundResolution::LEN,
+        seeds = [b"round_resolution", round.key().as_ref()],
+        bump
+    )]
+    pub resolution: Account<'info, RoundResolution>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+}
+
+/// 揭示结果指令所需的账户结构
+#[derive(Accounts)]
+pub struct RevealResult<'info> {
+    /// 裁定人
+    pub resolver: Signer<'info>,
+    
+    /// 赛事账户，轮次完成时更新待了结的轮次
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 赛事的裁定人集合
+    #[account(
+        seeds = [b"resolver_set", tournament.key().as_ref()],
+        bump = resolver_set.bump
+    )]
+    pub resolver_set: Account<'info, ResolverSet>,
+    
+    /// 裁定人质押记录
+    #[account(
+        mut,
+        seeds = [b"resolver_stake", tournament.key().as_ref(), resolver.key().as_ref()],
+        bump = resolver_stake.bump
+    )]
+    pub resolver_stake: Account<'info, ResolverStake>,
+    
+    /// 轮次账户
+    #[account(
+        mut,
+        constraint = round.tournament == tournament.key()
+    )]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 揭示的获胜队伍，验证队伍属于该轮次
+    #[account(
+        mut,
+        constraint = team.tournament == tournament.key(),
+        constraint = team.round == round.key() @ TournamentError::TeamNotInRound
+    )]
+    pub team: Account<'info, Team>,
+    
+    /// 轮次的承诺记录
+    #[account(
+        mut,
+        seeds = [b"round_resolution", round.key().as_ref()],
+        bump = resolution.bump
+    )]
+    pub resolution: Account<'info, RoundResolution>,
+}
+
+/// 罚没未揭示裁定人指令所需的账户结构
+#[derive(Accounts)]
+pub struct PenalizeUnrevealed<'info> {
+    /// 调用者，支付国库账户创建费用
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    
+    /// 状态账户，国库的所有者
+    #[account(
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 赛事账户
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 赛事的裁定人集合
+    #[account(
+        seeds = [b"resolver_set", tournament.key().as_ref()],
+        bump = resolver_set.bump
+    )]
+    pub resolver_set: Account<'info, ResolverSet>,
+    
+    /// 被罚没的裁定人质押记录
+    #[account(
+        mut,
+        has_one = tournament,
+        seeds = [b"resolver_stake", tournament.key().as_ref(), resolver_stake.resolver.as_ref()],
+        bump = resolver_stake.bump
+    )]
+    pub resolver_stake: Account<'info, ResolverStake>,
+    
+    /// 轮次账户
+    #[account(
+        constraint = round.tournament == tournament.key()
+    )]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 轮次的承诺记录
+    #[account(
+        mut,
+        seeds = [b"round_resolution", round.key().as_ref()],
+        bump = resolution.bump
+    )]
+    pub resolution: Account<'info, RoundResolution>,
+    
+    /// 赛事的裁定人质押托管账户
+    #[account(
+        mut,
+        seeds = [b"resolver_stake_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub resolver_stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 接收罚没的国库账户
+    #[account(
+        init_if_needed,
+        payer = caller,
+        token::mint = matchp_mint,
+        token::authority = state,
+        seeds = [b"treasury", matchp_mint.key().as_ref()],
+        bump
+    )]
+    pub treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// matchp代币铸造器
+    #[account(address = state.matchp_mint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
     
     /// 系统程序
     pub system_program: Program<'info, System>,
     /// 代币程序
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     /// 租金系统变量
     pub rent: Sysvar<'info, Rent>,
 }
 
-/// 创建轮次指令所需的账户结构
+/// 提取国库资金指令所需的账户结构
 #[derive(Accounts)]
-#[instruction(name: String, round_number: u8)]
-pub struct CreateTournamentRound<'info> {
-    /// 赛事管理员，支付创建费用
+pub struct WithdrawTreasury<'info> {
+    /// 合约管理员，必须是签名者
+    pub authority: Signer<'info>,
+    
+    /// 状态账户，验证调用者是合约管理员或国库管理员
+    #[account(
+        seeds = [b"state"],
+        bump,
+        constraint = state.authority == authority.key() || treasury_role.is_some() @ TournamentError::MissingRole
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 国库管理员角色，合约管理员本人调用时无需提供
+    #[account(
+        seeds = [b"role", [Role::TreasuryManager as u8].as_ref(), authority.key().as_ref()],
+        bump = treasury_role.bump
+    )]
+    pub treasury_role: Option<Account<'info, RoleAssignment>>,
+    
+    /// 国库代币账户
+    #[account(
+        mut,
+        seeds = [b"treasury", treasury.mint.as_ref()],
+        bump
+    )]
+    pub treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 接收提取资金的代币账户
+    #[account(
+        mut,
+        constraint = destination.mint == treasury.mint @ TournamentError::InvalidTokenAccount
+    )]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 国库代币的铸造器，matchp和没收的争议保证金代币各有一个国库
+    #[account(address = treasury.mint @ TournamentError::InvalidTokenMint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 配置争议期指令所需的账户结构
+#[derive(Accounts)]
+pub struct ConfigureDispute<'info> {
+    /// 赛事管理员，必须是签名者
+    pub authority: Signer<'info>,
+    
+    /// 赛事账户，验证调用者是管理员
+    #[account(
+        mut,
+        constraint = tournament.authority == authority.key(),
+        constraint = tournament.is_active
+    )]
+    pub tournament: Account<'info, Tournament>,
+}
+
+/// 发起争议指令所需的账户结构
+#[derive(Accounts)]
+pub struct ChallengeResult<'info> {
+    /// 争议发起人，缴纳保证金
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    
+    /// 状态账户，用于确定保证金代币
+    #[account(
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 赛事账户，记录未裁决的争议数量
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 轮次账户，验证轮次已完成且不在争议中
+    #[account(
+        mut,
+        constraint = round.tournament == tournament.key(),
+        constraint = round.is_completed,
+        constraint = !round.is_disputed @ TournamentError::RoundDisputed
+    )]
+    pub round: Account<'info, TournamentRound>,
+    
+    /// 发起人认为的获胜队伍，验证队伍属于该轮次
+    #[account(
+        constraint = proposed_team.round == round.key() @ TournamentError::TeamNotInRound
+    )]
+    pub proposed_team: Account<'info, Team>,
+    
+    /// 争议账户，每个轮次只能发起一次争议
+    #[account(
+        init,
+        payer = challenger,
+        space = 8 + Dispute::LEN,
+        seeds = [b"dispute", round.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+    
+    /// 保证金代币铸造器
+    pub bond_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 发起人的保证金代币账户
+    #[account(
+        mut,
+        constraint = challenger_bond_token.mint == bond_mint.key() @ TournamentError::InvalidTokenMint,
+        constraint = challenger_bond_token.owner == challenger.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub challenger_bond_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 托管保证金的代币账户
+    #[account(
+        init,
+        payer = challenger,
+        token::mint = bond_mint,
+        token::authority = tournament,
+        seeds = [b"dispute_bond", dispute.key().as_ref()],
+        bump
+    )]
+    pub dispute_bond_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 执行争议裁决所需的账户结构
+#[derive(Accounts)]
+pub struct DisputeRuling<'info> {
+    /// 赛事账户，记录未裁决的争议数量
+    #[account(mut)]
+    pub tournament: Box<Account<'info, Tournament>>,
+    
+    /// 争议中的轮次
+    #[account(
+        mut,
+        constraint = round.tournament == tournament.key()
+    )]
+    pub round: Box<Account<'info, TournamentRound>>,
+    
+    /// 争议账户
+    #[account(
+        mut,
+        has_one = round,
+        seeds = [b"dispute", round.key().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+    
+    /// 原获胜队伍
+    #[account(
+        mut,
+        address = dispute.original_team
+    )]
+    pub original_team: Box<Account<'info, Team>>,
+    
+    /// 发起人提出的获胜队伍
+    #[account(
+        mut,
+        address = dispute.proposed_team
+    )]
+    pub proposed_team: Box<Account<'info, Team>>,
+    
+    /// 争议发起人，接收托管账户的租金
+    /// CHECK: 地址与争议记录的发起人一致
+    #[account(
+        mut,
+        address = dispute.challenger
+    )]
+    pub challenger: UncheckedAccount<'info>,
+    
+    /// 托管保证金的代币账户
+    #[account(
+        mut,
+        seeds = [b"dispute_bond", dispute.key().as_ref()],
+        bump
+    )]
+    pub dispute_bond_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 发起人的保证金代币账户，推翻时退还保证金
+    #[account(
+        mut,
+        constraint = challenger_bond_token.mint == dispute.bond_mint @ TournamentError::InvalidTokenMint,
+        constraint = challenger_bond_token.owner == dispute.challenger @ TournamentError::InvalidTokenOwner
+    )]
+    pub challenger_bond_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 状态账户，国库的所有者
+    #[account(
+        seeds = [b"state"],
+        bump
+    )]
+    pub state: Box<Account<'info, State>>,
+    
+    /// 国库账户，维持原结果时没收保证金
+    #[account(
+        init_if_needed,
+        payer = payer,
+        token::mint = bond_mint,
+        token::authority = state,
+        seeds = [b"treasury", bond_mint.key().as_ref()],
+        bump
+    )]
+    pub treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 支付国库账户创建费用的签名者
     #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// 保证金代币铸造器
+    #[account(address = dispute.bond_mint @ TournamentError::InvalidBondMint)]
+    pub bond_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> DisputeRuling<'info> {
+    /// 执行裁决：推翻时更正获胜队伍并退还保证金，维持时保证金没收到国库
+    /// 裁决后关闭保证金托管账户，轮次恢复可结算状态
+    pub fn apply(&mut self, overturn: bool) -> Result<()> {
+        // 检查争议是否已裁决
+        require!(!self.dispute.is_resolved, TournamentError::DisputeAlreadyResolved);
+        
+        // 推翻时保证金退还发起人，维持时保证金没收到国库，赛事方不从争议结果中获利
+        let bond_destination = if overturn {
+            self.challenger_bond_token.to_account_info()
+        } else {
+            self.treasury.to_account_info()
+        };
+        
+        if overturn {
+            // 在任何结算之前更正获胜队伍
+            self.original_team.is_winner = false;
+            self.proposed_team.is_winner = true;
+            self.round.winner_team = self.proposed_team.key();
+            // 结果被推翻，赛事方有质押且开启罚没时质押待罚没
+            if self.tournament.is_staked && self.tournament.slash_bps > 0 {
+                self.round.slash_pending = true;
+                self.tournament.pending_slashes = self
+                    .tournament
+                    .pending_slashes
+                    .checked_add(1)
+                    .ok_or(TournamentError::Overflow)?;
+            }
+        }
+        
+        let authority_key = self.tournament.authority;
+        let seeds = &[
+            b"tournament".as_ref(),
+            authority_key.as_ref(),
+            &[self.tournament.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // 转出保证金
+        transfer_tokens(
+            self.token_program.to_account_info(),
+            self.dispute_bond_vault.to_account_info(),
+            &self.bond_mint,
+            bond_destination,
+            self.tournament.to_account_info(),
+            signer,
+            self.dispute.bond_amount,
+        )?;
+        
+        // 关闭托管账户，租金返还给发起人
+        token_interface::close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.dispute_bond_vault.to_account_info(),
+                destination: self.challenger.to_account_info(),
+                authority: self.tournament.to_account_info(),
+            },
+            signer,
+        ))?;
+        
+        self.dispute.is_resolved = true;
+        self.dispute.is_overturned = overturn;
+        
+        // 争议结束，轮次可以结算
+        self.round.is_disputed = false;
+        self.tournament.open_disputes = self
+            .tournament
+            .open_disputes
+            .checked_sub(1)
+            .ok_or(TournamentError::Overflow)?;
+        self.round.dispute_deadline = Clock::get()?.unix_timestamp;
+        
+        Ok(())
+    }
+}
+
+/// 裁决争议指令所需的账户结构
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    /// 仲裁人，必须是合约管理员或争议仲裁员
+    pub arbiter: Signer<'info>,
+    
+    /// 状态账户，验证调用者是合约管理员或争议仲裁员
+    #[account(
+        seeds = [b"state"],
+        bump,
+        constraint = state.authority == arbiter.key() || resolver_role.is_some() @ TournamentError::NotArbiter
+    )]
+    pub state: Account<'info, State>,
+    
+    /// 争议仲裁员角色，合约管理员本人调用时无需提供
+    #[account(
+        seeds = [b"role", [Role::Resolver as u8].as_ref(), arbiter.key().as_ref()],
+        bump = resolver_role.bump
+    )]
+    pub resolver_role: Option<Account<'info, RoleAssignment>>,
+    
+    /// 执行裁决所需的账户
+    pub ruling: DisputeRuling<'info>,
+}
+
+/// 配置代币投票治理指令所需的账户结构
+#[derive(Accounts)]
+pub struct ConfigureGovernance<'info> {
+    /// 赛事管理员，必须是签名者
     pub authority: Signer<'info>,
     
     /// 赛事账户，验证调用者是管理员
     #[account(
         mut,
         constraint = tournament.authority == authority.key(),
-        constraint = tournament.is_active == true
+        constraint = tournament.is_active
     )]
     pub tournament: Account<'info, Tournament>,
+}
+
+/// 争议投票指令所需的账户结构
+#[derive(Accounts)]
+pub struct CastDisputeVote<'info> {
+    /// 投票人，锁定vote代币
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    /// 赛事账户
+    pub tournament: Account<'info, Tournament>,
     
-    /// 轮次账户，使用PDA地址
+    /// 争议账户，验证争议属于该赛事
+    #[account(
+        mut,
+        constraint = dispute.tournament == tournament.key()
+    )]
+    pub dispute: Account<'info, Dispute>,
+    
+    /// 投票记录，每个投票人对每个争议只能投票一次
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + DisputeVote::LEN,
+        seeds = [b"dispute_vote", dispute.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub dispute_vote: Account<'info, DisputeVote>,
+    
+    /// 投票人的vote代币账户
+    #[account(
+        mut,
+        constraint = voter_vote_token.mint == tournament.vote_mint @ TournamentError::InvalidTokenAccount,
+        constraint = voter_vote_token.owner == voter.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub voter_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 锁定投票vote代币的托管账户
+    #[account(
+        init_if_needed,
+        payer = voter,
+        token::mint = vote_mint,
+        token::authority = tournament,
+        seeds = [b"governance_vault", dispute.key().as_ref()],
+        bump
+    )]
+    pub governance_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 系统程序
+    pub system_program: Program<'info, System>,
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 租金系统变量
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 结束争议投票指令所需的账户结构
+#[derive(Accounts)]
+pub struct FinalizeDisputeVote<'info> {
+    /// 调用者，任何人都可以在投票期结束后调用
+    pub caller: Signer<'info>,
+    
+    /// 执行裁决所需的账户
+    pub ruling: DisputeRuling<'info>,
+}
+
+/// 取回争议投票指令所需的账户结构
+#[derive(Accounts)]
+pub struct WithdrawDisputeVote<'info> {
+    /// 投票人，取回锁定的vote代币
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    /// 赛事账户
+    pub tournament: Account<'info, Tournament>,
+    
+    /// 争议账户，验证争议属于该赛事
+    #[account(
+        constraint = dispute.tournament == tournament.key()
+    )]
+    pub dispute: Account<'info, Dispute>,
+    
+    /// 投票记录，取回后关闭
+    #[account(
+        mut,
+        has_one = voter,
+        has_one = dispute,
+        close = voter,
+        seeds = [b"dispute_vote", dispute.key().as_ref(), voter.key().as_ref()],
+        bump = dispute_vote.bump
+    )]
+    pub dispute_vote: Account<'info, DisputeVote>,
+    
+    /// 投票人接收vote代币的账户
+    #[account(
+        mut,
+        constraint = voter_vote_token.mint == governance_vault.mint @ TournamentError::InvalidTokenAccount,
+        constraint = voter_vote_token.owner == voter.key() @ TournamentError::InvalidTokenAccount
+    )]
+    pub voter_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 锁定投票vote代币的托管账户
     #[account(
-        init,
-        payer = authority,
-        space = 8 + TournamentRound::LEN,
-        seeds = [b"round", tournament.key().as_ref(), &[round_number]],
+        mut,
+        seeds = [b"governance_vault", dispute.key().as_ref()],
         bump
     )]
-    pub round: Account<'info, TournamentRound>,
+    pub governance_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     
-    /// 系统程序
-    pub system_program: Program<'info, System>,
-    /// 租金系统变量
-    pub rent: Sysvar<'info, Rent>,
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
-/// 创建团队指令所需的账户结构
+/// 配置质押罚没指令所需的账户结构
 #[derive(Accounts)]
-#[instruction(name: String)]
-pub struct CreateTeam<'info> {
-    /// 赛事管理员，支付创建费用
-    #[account(mut)]
+pub struct ConfigureSlashing<'info> {
+    /// 合约管理员，必须是签名者
     pub authority: Signer<'info>,
     
-    /// 赛事账户，验证调用者是管理员
+    /// 状态账户，验证调用者是合约管理员或全局管理员
     #[account(
-        mut,
-        constraint = tournament.authority == authority.key(),
-        constraint = tournament.is_active == true
+        seeds = [b"state"],
+        bump,
+        constraint = state.authority == authority.key() || admin_role.is_some() @ TournamentError::MissingRole
     )]
-    pub tournament: Account<'info, Tournament>,
+    pub state: Account<'info, State>,
     
-    /// 轮次账户，验证轮次属于该赛事
+    /// 全局管理员角色，合约管理员本人调用时无需提供
     #[account(
-        constraint = round.tournament == tournament.key(),
-        constraint = round.is_active == true
+        seeds = [b"role", [Role::Admin as u8].as_ref(), authority.key().as_ref()],
+        bump = admin_role.bump
     )]
-    pub round: Account<'info, TournamentRound>,
+    pub admin_role: Option<Account<'info, RoleAssignment>>,
     
-    /// 团队账户，使用PDA地址
+    /// 赛事账户
     #[account(
-        init,
-        payer = authority,
-        space = 8 + Team::LEN,
-        seeds = [b"team", round.key().as_ref(), name.as_bytes()],
-        bump
+        mut,
+        constraint = tournament.is_active
     )]
-    pub team: Account<'info, Team>,
-    
-    /// 系统程序
-    pub system_program: Program<'info, System>,
-    /// 租金系统变量
-    pub rent: Sysvar<'info, Rent>,
+    pub tournament: Account<'info, Tournament>,
 }
 
-/// 下注指令所需的账户结构
+/// 罚没赛事方质押指令所需的账户结构
 #[derive(Accounts)]
-#[instruction(amount: u64)]
-pub struct PlaceBet<'info> {
-    /// 下注用户，支付下注费用
+pub struct SlashStake<'info> {
+    /// 调用者，支付补偿池和国库账户的创建费用
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub caller: Signer<'info>,
     
-    /// 赛事账户，验证赛事是否激活
+    /// 状态账户，国库的所有者
     #[account(
-        constraint = tournament.is_active == true
+        seeds = [b"state"],
+        bump
     )]
-    pub tournament: Account<'info, Tournament>,
+    pub state: Account<'info, State>,
     
-    /// 轮次账户，验证轮次是否激活
+    /// 赛事账户
     #[account(
         mut,
-        constraint = round.tournament == tournament.key(),
-        constraint = round.is_active == true
+        constraint = tournament.is_active @ TournamentError::TournamentNotActive
     )]
-    pub round: Account<'info, TournamentRound>,
+    pub tournament: Box<Account<'info, Tournament>>,
     
-    /// 团队账户，验证团队是否属于该轮次
+    /// 被罚没的轮次
     #[account(
-        constraint = team.tournament == tournament.key(),
-        constraint = team.round == round.key()
+        mut,
+        constraint = round.tournament == tournament.key()
     )]
-    pub team: Account<'info, Team>,
+    pub round: Box<Account<'info, TournamentRound>>,
     
-    /// 下注账户
+    /// 赛事的matchp质押账户
     #[account(
-        init,
-        payer = user,
-        space = 8 + Bet::LEN
+        mut,
+        constraint = tournament_matchp_token.mint == state.matchp_mint @ TournamentError::InvalidTokenAccount,
+        seeds = [b"tournament_matchp", tournament.key().as_ref()],
+        bump
     )]
-    pub bet: Account<'info, Bet>,
+    pub tournament_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
     
-    /// 用户的wanzi代币账户
-    #[account(mut)]
-    pub user_wanzi_token: Box<Account<'info, TokenAccount>>,
-    /// 用户的vote代币账户
-    #[account(mut)]
-    pub user_vote_token: Box<Account<'info, TokenAccount>>,
+    /// 轮次的争议账户，结果被推翻导致的罚没必须提供
+    #[account(
+        has_one = round,
+        seeds = [b"dispute", round.key().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Option<Box<Account<'info, Dispute>>>,
+    
+    /// 争议发起人的matchp代币账户，接收推翻结果的奖励
+    #[account(
+        mut,
+        constraint = challenger_matchp_token.mint == state.matchp_mint @ TournamentError::InvalidTokenMint
+    )]
+    pub challenger_matchp_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
     
-    /// 赛事的wanzi代币账户
+    /// 轮次的补偿池
     #[account(
         init_if_needed,
-        payer = user,
-        token::mint = wanzi_mint,
+        payer = caller,
+        token::mint = matchp_mint,
         token::authority = tournament,
-        seeds = [b"tournament_wanzi", tournament.key().as_ref()],
+        seeds = [b"compensation", round.key().as_ref()],
         bump
     )]
-    pub tournament_wanzi_token: Box<Account<'info, TokenAccount>>,
+    pub compensation_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     
-    /// 赛事的vote代币账户
+    /// 国库账户，轮次没有下注时接收罚没
     #[account(
         init_if_needed,
-        payer = user,
-        token::mint = vote_mint,
-        token::authority = tournament,
-        seeds = [b"tournament_vote", tournament.key().as_ref()],
+        payer = caller,
+        token::mint = matchp_mint,
+        token::authority = state,
+        seeds = [b"treasury", matchp_mint.key().as_ref()],
         bump
     )]
-    pub tournament_vote_token: Box<Account<'info, TokenAccount>>,
+    pub treasury: Box<InterfaceAccount<'info, TokenAccount>>,
     
-    /// wanzi代币铸造器
-    pub wanzi_mint: Box<Account<'info, Mint>>,
-    /// vote代币铸造器
-    pub vote_mint: Box<Account<'info, Mint>>,
+    /// matchp代币铸造器
+    #[account(address = state.matchp_mint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
     
     /// 系统程序
     pub system_program: Program<'info, System>,
     /// 代币程序
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     /// 租金系统变量
     pub rent: Sysvar<'info, Rent>,
 }
 
-/// 完成轮次指令所需的账户结构
+/// 标记轮次过期指令所需的账户结构
 #[derive(Accounts)]
-pub struct CompleteRound<'info> {
-    /// 赛事管理员，必须是签名者
+pub struct ExpireRound<'info> {
+    /// 调用者，任何人都可以在裁定期限过后调用
+    pub caller: Signer<'info>,
+    
+    /// 赛事账户，更新待了结的轮次和待罚没数量
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub tournament: Account<'info, Tournament>,
     
-    /// 赛事账户，验证调用者是管理员
+    /// 过期的轮次
     #[account(
         mut,
-        constraint = tournament.authority == authority.key(),
-        constraint = tournament.is_active == true
+        constraint = round.tournament == tournament.key()
     )]
+    pub round: Account<'info, TournamentRound>,
+}
+
+/// 过期轮次退款指令所需的账户结构
+#[derive(Accounts)]
+pub struct RefundBet<'info> {
+    /// 赛事账户
     pub tournament: Account<'info, Tournament>,
     
-    /// 轮次账户，验证轮次是否激活
+    /// 轮次账户，验证轮次已过期
     #[account(
-        mut,
         constraint = round.tournament == tournament.key(),
-        constraint = round.is_active == true
+        constraint = round.is_expired @ TournamentError::RoundNotExpired
     )]
     pub round: Account<'info, TournamentRound>,
     
-    /// 获胜团队账户，验证团队是否属于该轮次
+    /// 下注账户，验证下注属于该轮次且未结算
     #[account(
         mut,
-        constraint = winner_team.tournament == tournament.key(),
-        constraint = winner_team.round == round.key()
+        constraint = bet.tournament == tournament.key(),
+        constraint = bet.round == round.key() @ TournamentError::BetNotInRound,
+        constraint = !bet.is_settled
     )]
-    pub winner_team: Account<'info, Team>,
+    pub bet: Box<Account<'info, Bet>>,
+    
+    /// 下注NFT的持有者，必须是签名者
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// 持有者的下注NFT代币账户，验证持有该下注的NFT
+    #[account(
+        mut,
+        constraint = user_bet_nft.mint == bet.mint,
+        constraint = user_bet_nft.owner == user.key() @ TournamentError::NotBetOwner,
+        constraint = user_bet_nft.amount == 1 @ TournamentError::NotBetOwner
+    )]
+    pub user_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 下注NFT铸造器
+    #[account(
+        mut,
+        address = bet.mint
+    )]
+    pub bet_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 下注NFT的元数据账户
+    /// CHECK: 由Metaplex元数据程序校验
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), bet_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub bet_metadata: UncheckedAccount<'info>,
+    
+    /// 下注NFT的主版本账户
+    /// CHECK: 由Metaplex元数据程序校验
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), bet_mint.key().as_ref(), b"edition"],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub bet_master_edition: UncheckedAccount<'info>,
+    
+    /// 用户的下注代币账户，SOL赛事无需提供
+    #[account(
+        mut,
+        constraint = user_currency_token.mint == tournament.currency_mint @ TournamentError::InvalidTokenMint,
+        constraint = user_currency_token.owner == user.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub user_currency_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    
+    /// 用户的vote代币账户
+    #[account(
+        mut,
+        constraint = user_vote_token.mint == tournament.vote_mint @ TournamentError::InvalidTokenMint,
+        constraint = user_vote_token.owner == user.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub user_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的下注代币托管账户，SOL赛事无需提供
+    #[account(
+        mut,
+        token::token_program = currency_token_program,
+        seeds = [b"currency_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub currency_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// SOL赛事的下注托管账户
+    #[account(
+        mut,
+        seeds = [b"sol_vault", tournament.key().as_ref()],
+        bump = sol_vault.bump
+    )]
+    pub sol_vault: Option<Box<Account<'info, SolVault>>>,
+    
+    /// 赛事的vote代币账户
+    #[account(
+        mut,
+        seeds = [b"tournament_vote", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// 赛事的下注代币铸造器，SOL赛事无需提供
+    #[account(
+        address = tournament.currency_mint @ TournamentError::InvalidCurrencyMint,
+        mint::token_program = currency_token_program
+    )]
+    pub currency_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// 代币程序
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 下注代币的代币程序，可以与matchp和vote使用的代币程序不同
+    pub currency_token_program: Interface<'info, TokenInterface>,
+    /// 下注NFT使用的SPL Token程序
+    pub nft_token_program: Program<'info, Token>,
+    /// Metaplex元数据程序
+    pub token_metadata_program: Program<'info, Metadata>,
 }
 
-/// 结算下注指令所需的账户结构
+/// 领取罚没补偿指令所需的账户结构
 #[derive(Accounts)]
-pub struct SettleBet<'info> {
-    /// 赛事管理员，必须是签名者
-    #[account(mut)]
-    pub authority: Signer<'info>,
+pub struct ClaimCompensation<'info> {
+    /// 下注持有者
+    pub user: Signer<'info>,
     
     /// 赛事账户
     pub tournament: Account<'info, Tournament>,
     
-    /// 轮次账户，验证轮次是否已完成
+    /// 状态账户，用于校验matchp代币
     #[account(
-        constraint = round.tournament == tournament.key(),
-        constraint = round.is_completed == true
+        seeds = [b"state"],
+        bump
     )]
-    pub round: Account<'info, TournamentRound>,
+    pub state: Box<Account<'info, State>>,
     
-    /// 获胜团队账户，验证团队是否获胜
+    /// 轮次账户
     #[account(
-        constraint = winner_team.tournament == tournament.key(),
-        constraint = winner_team.round == round.key(),
-        constraint = winner_team.is_winner == true
+        constraint = round.tournament == tournament.key()
     )]
-    pub winner_team: Account<'info, Team>,
+    pub round: Account<'info, TournamentRound>,
     
-    /// 下注账户，验证下注是否未结算
+    /// 下注账户，只有队伍下注参与补偿
     #[account(
         mut,
-        constraint = bet.tournament == tournament.key(),
-        constraint = bet.round == round.key(),
-        constraint = bet.is_settled == false
+        constraint = bet.round == round.key() @ TournamentError::BetNotInRound,
+        constraint = bet.market == Pubkey::default() @ TournamentError::InvalidBetMarket
     )]
     pub bet: Account<'info, Bet>,
     
-    /// 下注用户账户
-    /// CHECK: 这是下注的用户
-    pub user: AccountInfo<'info>,
+    /// 持有者的下注NFT代币账户，领取未结算下注的补偿时必须提供
+    pub user_bet_nft: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
     
-    /// 用户的wanzi代币账户
+    /// 轮次的补偿池
     #[account(
         mut,
-        constraint = user_wanzi_token.owner == user.key()
+        seeds = [b"compensation", round.key().as_ref()],
+        bump
     )]
-    pub user_wanzi_token: Box<Account<'info, TokenAccount>>,
+    pub compensation_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     
-    /// 用户的vote代币账户
+    /// 用户接收补偿的matchp代币账户
     #[account(
         mut,
-        constraint = user_vote_token.owner == user.key()
+        constraint = user_matchp_token.mint == compensation_vault.mint @ TournamentError::InvalidTokenAccount,
+        constraint = user_matchp_token.owner == user.key() @ TournamentError::InvalidTokenAccount
     )]
-    pub user_vote_token: Box<Account<'info, TokenAccount>>,
+    pub user_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
     
-    /// 赛事的wanzi代币账户
-    #[account(mut)]
-    pub tournament_wanzi_token: Box<Account<'info, TokenAccount>>,
-    /// 赛事的vote代币账户
-    #[account(mut)]
-    pub tournament_vote_token: Box<Account<'info, TokenAccount>>,
+    /// matchp代币铸造器
+    #[account(address = state.matchp_mint @ TournamentError::InvalidMatchpMint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
     
     /// 代币程序
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
-/// 关闭赛事指令所需的账户结构
+/// 结算市场下注指令所需的账户结构
 #[derive(Accounts)]
-pub struct CloseTournament<'info> {
-    /// 赛事管理员，必须是签名者
+pub struct SettleMarketBet<'info> {
+    /// 赛事账户，记录手续费奖励
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub tournament: Account<'info, Tournament>,
     
-    /// 赛事账户，验证调用者是管理员
+    /// 市场账户，验证市场已出结果
+    #[account(
+        constraint = market.tournament == tournament.key(),
+        constraint = market.is_resolved
+    )]
+    pub market: Box<Account<'info, Market>>,
+    
+    /// 市场所属的轮次，验证争议期已结束
+    #[account(address = market.round)]
+    pub round: Box<Account<'info, TournamentRound>>,
+    
+    /// 下注账户，验证下注属于该市场且未结算
     #[account(
         mut,
-        has_one = authority,
+        constraint = bet.tournament == tournament.key(),
+        constraint = bet.market == market.key(),
+        constraint = !bet.is_settled
     )]
-    pub tournament: Account<'info, Tournament>,
+    pub bet: Box<Account<'info, Bet>>,
     
-    /// 赛事的matchp代币账户
-    #[account(mut)]
-    pub tournament_matchp_token: Box<Account<'info, TokenAccount>>,
-    /// 管理员的matchp代币账户
+    /// 下注NFT的持有者，必须是签名者
     #[account(mut)]
-    pub authority_matchp_token: Box<Account<'info, TokenAccount>>,
+    pub user: Signer<'info>,
     
-    /// 赛事的vote代币账户
-    #[account(mut)]
-    pub tournament_vote_token: Box<Account<'info, TokenAccount>>,
-    /// 赛事的wanzi代币账户
-    #[account(mut)]
-    pub tournament_wanzi_token: Box<Account<'info, TokenAccount>>,
-    /// 管理员的wanzi代币账户
-    #[account(mut)]
-    pub authority_wanzi_token: Box<Account<'info, TokenAccount>>,
+    /// 持有者的下注NFT代币账户，验证持有该下注的NFT
+    #[account(
+        mut,
+        constraint = user_bet_nft.mint == bet.mint,
+        constraint = user_bet_nft.owner == user.key() @ TournamentError::NotBetOwner,
+        constraint = user_bet_nft.amount == 1 @ TournamentError::NotBetOwner
+    )]
+    pub user_bet_nft: Box<InterfaceAccount<'info, TokenAccount>>,
     
-    /// vote代币铸造器
-    pub vote_mint: Box<Account<'info, Mint>>,
+    /// 下注NFT铸造器
+    #[account(
+        mut,
+        address = bet.mint
+    )]
+    pub bet_mint: Box<InterfaceAccount<'info, Mint>>,
     
-    /// 代币程序
-    pub token_program: Program<'info, Token>,
-}
-
-/// 更新代币水龙头地址指令所需的账户结构
-#[derive(Accounts)]
-pub struct UpdateTokenFaucet<'info> {
-    /// 合约管理员，必须是签名者
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// 下注NFT的元数据账户
+    /// CHECK: 由Metaplex元数据程序校验
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), bet_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub bet_metadata: UncheckedAccount<'info>,
     
-    /// 状态账户，验证调用者是管理员
+    /// 下注NFT的主版本账户
+    /// CHECK: 由Metaplex元数据程序校验
     #[account(
         mut,
-        has_one = authority,
-        seeds = [b"state"],
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), bet_mint.key().as_ref(), b"edition"],
+        seeds::program = token_metadata_program.key(),
         bump
     )]
-    pub state: Account<'info, State>,
-}
-
-/// 质押赛事指令所需的账户结构
-#[derive(Accounts)]
-pub struct StakeTournament<'info> {
-    /// 赛事管理员，支付质押费用
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    pub bet_master_edition: UncheckedAccount<'info>,
     
-    /// 赛事账户，验证调用者是管理员
+    /// 用户的下注代币账户，SOL赛事无需提供
     #[account(
         mut,
-        constraint = tournament.authority == authority.key(),
-        constraint = tournament.is_active == true,
-        seeds = [b"tournament", authority.key().as_ref()],
-        bump = tournament.bump
+        constraint = user_currency_token.mint == tournament.currency_mint @ TournamentError::InvalidTokenMint,
+        constraint = user_currency_token.owner == user.key() @ TournamentError::InvalidTokenOwner
     )]
-    pub tournament: Account<'info, Tournament>,
+    pub user_currency_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
     
-    /// 管理员的matchp代币账户
-    #[account(mut)]
-    pub authority_matchp_token: Box<Account<'info, TokenAccount>>,
+    /// 用户的vote代币账户
+    #[account(
+        mut,
+        constraint = user_vote_token.mint == tournament.vote_mint @ TournamentError::InvalidTokenMint,
+        constraint = user_vote_token.owner == user.key() @ TournamentError::InvalidTokenOwner
+    )]
+    pub user_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
     
-    /// 管理员的vote代币账户
-    #[account(mut)]
-    pub authority_vote_token: Box<Account<'info, TokenAccount>>,
+    /// 赛事的下注代币托管账户，SOL赛事无需提供
+    #[account(
+        mut,
+        token::token_program = currency_token_program,
+        seeds = [b"currency_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub currency_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// SOL赛事的下注托管账户
+    #[account(
+        mut,
+        seeds = [b"sol_vault", tournament.key().as_ref()],
+        bump = sol_vault.bump
+    )]
+    pub sol_vault: Option<Box<Account<'info, SolVault>>>,
     
-    /// 赛事的matchp代币账户
+    /// 赛事的vote代币账户
     #[account(
         mut,
-        constraint = tournament_matchp_token.mint == matchp_mint.key()
+        seeds = [b"tournament_vote", tournament.key().as_ref()],
+        bump
     )]
-    pub tournament_matchp_token: Box<Account<'info, TokenAccount>>,
+    pub tournament_vote_token: Box<InterfaceAccount<'info, TokenAccount>>,
     
-    /// matchp代币铸造器
-    pub matchp_mint: Box<Account<'info, Mint>>,
-    /// vote代币铸造器
+    /// 质押奖励池，收取手续费时必须提供
     #[account(
         mut,
-        constraint = vote_mint.mint_authority.unwrap() == tournament.key()
+        seeds = [b"rewards_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub rewards_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    
+    /// 赛事的下注代币铸造器，SOL赛事无需提供
+    #[account(
+        address = tournament.currency_mint @ TournamentError::InvalidCurrencyMint,
+        mint::token_program = currency_token_program
     )]
-    pub vote_mint: Box<Account<'info, Mint>>,
+    pub currency_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    /// 赛事专属的vote代币铸造器
+    #[account(address = tournament.vote_mint @ TournamentError::InvalidVoteMint)]
+    pub vote_mint: Box<InterfaceAccount<'info, Mint>>,
     
     /// 代币程序
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 下注代币的代币程序，可以与matchp和vote使用的代币程序不同
+    pub currency_token_program: Interface<'info, TokenInterface>,
+    /// 下注NFT使用的SPL Token程序
+    pub nft_token_program: Program<'info, Token>,
+    /// Metaplex元数据程序
+    pub token_metadata_program: Program<'info, Metadata>,
 }
 
 /// 状态账户数据结构
@@ -919,15 +6533,73 @@ pub struct State {
     pub wanzi_mint: Pubkey,
     /// matchp代币铸造器地址
     pub matchp_mint: Pubkey,
-    /// vote代币铸造器地址
+    /// 全局vote代币铸造器地址，已由各赛事专属的vote铸造器取代，仅为兼容保留
     pub vote_mint: Pubkey,
     /// 代币水龙头程序ID
     pub token_faucet_program_id: Pubkey,
+    /// 待接受的新管理员，没有进行中的转移时为默认公钥
+    pub pending_authority: Pubkey,
+    /// 全局暂停，暂停期间禁止下注、质押和创建赛事
+    pub paused: bool,
+    /// 赛事创建模式
+    pub creation_mode: CreationMode,
+    /// 最低质押创建模式下，创建赛事需要质押的matchp数量
+    pub min_creation_stake: u64,
 }
 
 impl State {
     /// 状态账户数据大小
-    pub const LEN: usize = 32 + 32 + 32 + 32 + 32;
+    pub const LEN: usize = 32 + 32 + 32 + 32 + 32 + 32 + 1 + 1 + 8;
+}
+
+/// SOL下注托管账户数据结构
+/// 由本程序拥有，租金之外的lamports即为赛事的SOL彩池
+#[account]
+pub struct SolVault {
+    /// 关联的赛事
+    pub tournament: Pubkey,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl SolVault {
+    /// SOL托管账户数据大小
+    pub const LEN: usize = 32 + // tournament
+                          1;   // bump
+}
+
+/// 下注代币白名单账户数据结构
+#[account]
+pub struct CurrencyAllowlist {
+    /// 允许作为赛事下注代币的铸造器列表，wanzi无需加入
+    pub mints: Vec<Pubkey>,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl CurrencyAllowlist {
+    /// 下注代币白名单账户数据大小
+    pub const LEN: usize = 4 + 32 * MAX_CURRENCIES + // mints
+                          1;   // bump
+}
+
+/// 角色账户数据结构
+/// 地址由角色和持有者派生，账户存在即代表持有该角色
+#[account]
+pub struct RoleAssignment {
+    /// 角色
+    pub role: Role,
+    /// 角色持有者
+    pub holder: Pubkey,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl RoleAssignment {
+    /// 角色账户数据大小
+    pub const LEN: usize = 1 +  // role
+                          32 + // holder
+                          1;   // bump
 }
 
 /// 赛事账户数据结构
@@ -937,7 +6609,7 @@ pub struct Tournament {
     pub authority: Pubkey,
     /// 赛事名称
     pub name: String,
-    /// 质押金额
+    /// 质押池中的matchp总量(赛事方和委托者)，罚没时按比例扣减
     pub stake_amount: u64,
     /// 是否激活
     pub is_active: bool,
@@ -947,6 +6619,48 @@ pub struct Tournament {
     pub created_at: i64,
     /// PDA bump
     pub bump: u8,
+    /// 完成轮次所需的裁定人票数，0表示由管理员单独裁定
+    pub resolver_threshold: u8,
+    /// 轮次完成后的争议期时长(秒)
+    pub dispute_period: i64,
+    /// 发起争议所需的保证金
+    pub dispute_bond: u64,
+    /// 争议投票期时长(秒)
+    pub governance_vote_period: i64,
+    /// 争议投票的法定票数，0表示由管理员仲裁
+    pub governance_quorum: u64,
+    /// 结果预言机程序，默认公钥表示不使用预言机
+    pub oracle_program: Pubkey,
+    /// 预言机结果的发布者，只接受该账户发布的结果
+    pub oracle_publisher: Pubkey,
+    /// 预言机结果的最长有效期(秒)
+    pub oracle_max_staleness: i64,
+    /// 每次罚没质押的比例(基点)
+    pub slash_bps: u16,
+    /// 轮次创建后的裁定期限(秒)，0表示没有期限
+    pub resolution_period: i64,
+    /// 赛事专属的vote代币铸造器
+    pub vote_mint: Pubkey,
+    /// 从获胜奖金中抽取的手续费比例(基点)
+    pub fee_bps: u16,
+    /// 质押池的份额总数(赛事方和委托者)，用于分配手续费奖励和罚没损失
+    pub reward_shares: u64,
+    /// 每份质押累计的奖励，按REWARD_PRECISION放大
+    pub acc_reward_per_share: u128,
+    /// 赛事的下注代币铸造器，未质押时使用该代币下注
+    pub currency_mint: Pubkey,
+    /// 赛事暂停，暂停期间禁止下注和质押，退款和领取不受影响
+    pub paused: bool,
+    /// 未裁决的争议数量，大于0时不能修改争议和治理配置
+    pub open_disputes: u32,
+    /// 未出结果且未过期的轮次数量
+    pub unresolved_rounds: u32,
+    /// 等待罚没的轮次数量
+    pub pending_slashes: u32,
+    /// 已完成轮次中最晚的争议期截止时间
+    pub dispute_window_end: i64,
+    /// 有下注或轮次出结果后锁定手续费配置
+    pub fees_locked: bool,
 }
 
 impl Tournament {
@@ -958,7 +6672,99 @@ impl Tournament {
                           1 +  // is_active
                           1 +  // is_staked
                           8 +  // created_at
-                          1;   // bump
+                          1 +  // bump
+                          1 +  // resolver_threshold
+                          8 +  // dispute_period
+                          8 +  // dispute_bond
+                          8 +  // governance_vote_period
+                          8 +  // governance_quorum
+                          32 + // oracle_program
+                          32 + // oracle_publisher
+                          8 +  // oracle_max_staleness
+                          2 +  // slash_bps
+                          8 +  // resolution_period
+                          32 + // vote_mint
+                          2 +  // fee_bps
+                          8 +  // reward_shares
+                          16 + // acc_reward_per_share
+                          32 + // currency_mint
+                          1 +  // paused
+                          4 +  // open_disputes
+                          4 +  // unresolved_rounds
+                          4 +  // pending_slashes
+                          8 +  // dispute_window_end
+                          1;   // fees_locked
+
+    /// 记录轮次出结果，争议期截止前赛事仍有待了结的结果
+    pub fn record_round_result(&mut self, dispute_deadline: i64) -> Result<()> {
+        self.unresolved_rounds = self
+            .unresolved_rounds
+            .checked_sub(1)
+            .ok_or(TournamentError::Overflow)?;
+        self.dispute_window_end = self.dispute_window_end.max(dispute_deadline);
+        // 结果已知后手续费不能再修改
+        self.fees_locked = true;
+        Ok(())
+    }
+
+    /// 是否有未了结的轮次：未出结果且未过期的轮次、未结束的争议期、未裁决的争议或待执行的罚没
+    pub fn has_unsettled_rounds(&self, now: i64) -> bool {
+        self.unresolved_rounds > 0
+            || self.open_disputes > 0
+            || self.pending_slashes > 0
+            || now < self.dispute_window_end
+    }
+
+    /// 赛事是否使用原生SOL下注
+    pub fn is_sol_currency(&self) -> bool {
+        self.currency_mint == native_mint::ID
+    }
+
+    /// 按当前每份价值计算存入matchp应得的份额
+    pub fn shares_for_deposit(&self, amount: u64) -> Result<u64> {
+        if self.reward_shares == 0 || self.stake_amount == 0 {
+            return Ok(amount);
+        }
+        u64::try_from(amount as u128 * self.reward_shares as u128 / self.stake_amount as u128)
+            .map_err(|_| error!(TournamentError::Overflow))
+    }
+
+    /// 赎回matchp需要扣除的份额，向上取整避免赎回者多拿
+    pub fn shares_for_withdrawal(&self, amount: u64) -> Result<u64> {
+        require!(self.stake_amount > 0, TournamentError::InvalidStakeAmount);
+        let shares = (amount as u128 * self.reward_shares as u128)
+            .div_ceil(self.stake_amount as u128);
+        u64::try_from(shares).map_err(|_| error!(TournamentError::Overflow))
+    }
+
+    /// 份额对应的matchp价值，罚没后每份价值下降
+    pub fn stake_value(&self, shares: u64) -> u64 {
+        if self.reward_shares == 0 {
+            return 0;
+        }
+        (shares as u128 * self.stake_amount as u128 / self.reward_shares as u128) as u64
+    }
+
+    /// 计算奖金应扣除的手续费，没有质押份额时不收取
+    pub fn bet_fee(&self, amount: u64) -> u64 {
+        if self.reward_shares == 0 {
+            return 0;
+        }
+        (amount as u128 * self.fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// 将手续费按份额累计到每份质押的奖励中
+    pub fn distribute_rewards(&mut self, amount: u64) -> Result<()> {
+        let increment = (amount as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(TournamentError::Overflow)?
+            / self.reward_shares as u128;
+        self.acc_reward_per_share = self
+            .acc_reward_per_share
+            .checked_add(increment)
+            .ok_or(TournamentError::Overflow)?;
+        Ok(())
+    }
 }
 
 /// 轮次账户数据结构
@@ -978,10 +6784,26 @@ pub struct TournamentRound {
     pub created_at: i64,
     /// PDA bump
     pub bump: u8,
-    /// 该轮次中WANZI代币的总下注额
-    pub total_wanzi_bets: u64,
+    /// 该轮次中赛事下注代币的总下注额
+    pub total_currency_bets: u64,
     /// 该轮次中VOTE代币的总下注额
     pub total_vote_bets: u64,
+    /// 获胜队伍
+    pub winner_team: Pubkey,
+    /// 争议期截止时间，之后才能结算
+    pub dispute_deadline: i64,
+    /// 是否存在未裁决的争议
+    pub is_disputed: bool,
+    /// 裁定截止时间，0表示没有期限
+    pub resolution_deadline: i64,
+    /// 是否因超过裁定期限而过期
+    pub is_expired: bool,
+    /// 结果被推翻或轮次过期后等待罚没
+    pub slash_pending: bool,
+    /// 是否已罚没过赛事方质押
+    pub is_slashed: bool,
+    /// 罚没后可供下注者领取的matchp补偿总额
+    pub compensation_pool: u64,
 }
 
 impl TournamentRound {
@@ -993,8 +6815,35 @@ impl TournamentRound {
                           1 +  // is_completed
                           8 +  // created_at
                           1 +  // bump
-                          8 +  // total_wanzi_bets (u64)
-                          8;   // total_vote_bets (u64)
+                          8 +  // total_currency_bets (u64)
+                          8 +  // total_vote_bets (u64)
+                          32 + // winner_team
+                          8 +  // dispute_deadline
+                          1 +  // is_disputed
+                          8 +  // resolution_deadline
+                          1 +  // is_expired
+                          1 +  // slash_pending
+                          1 +  // is_slashed
+                          8;   // compensation_pool
+
+    /// 标记轮次为完成状态，将获胜队伍标记为获胜，并开启到dispute_deadline为止的争议期
+    pub fn complete(&mut self, winner_team: &mut Account<Team>, dispute_deadline: i64) {
+        self.is_active = false;
+        self.is_completed = true;
+        self.winner_team = winner_team.key();
+        self.dispute_deadline = dispute_deadline;
+        winner_team.is_winner = true;
+    }
+
+    /// 检查是否还能报告比分：轮次未过期、没有争议且未超过裁定期限
+    pub fn check_score_reporting(&self, now: i64) -> Result<()> {
+        require!(!self.is_disputed, TournamentError::RoundDisputed);
+        require!(
+            !self.is_expired && (self.resolution_deadline == 0 || now <= self.resolution_deadline),
+            TournamentError::ResolutionDeadlinePassed
+        );
+        Ok(())
+    }
 }
 
 /// 团队账户数据结构
@@ -1012,6 +6861,10 @@ pub struct Team {
     pub is_eliminated: bool,
     /// PDA bump
     pub bump: u8,
+    /// 该轮次的得分
+    pub score: u64,
+    /// 得分是否已报告
+    pub is_scored: bool,
 }
 
 impl Team {
@@ -1021,7 +6874,9 @@ impl Team {
                           32 + // name (max length)
                           1 +  // is_winner
                           1 +  // is_eliminated
-                          1;   // bump
+                          1 +  // bump
+                          8 +  // score
+                          1;   // is_scored
 }
 
 /// 下注账户数据结构
@@ -1033,7 +6888,7 @@ pub struct Bet {
     pub round: Pubkey,
     /// 下注的队伍
     pub team: Pubkey,
-    /// 下注的用户
+    /// 下注的持有者，通过本程序转让或结算时更新；NFT在外部交易时以NFT持有者为准
     pub user: Pubkey,
     /// 下注金额
     pub amount: u64,
@@ -1043,6 +6898,14 @@ pub struct Bet {
     pub is_settled: bool,
     /// 是否获胜
     pub is_winner: bool,
+    /// 代表该下注的NFT铸造器
+    pub mint: Pubkey,
+    /// 关联的数值市场，队伍下注为默认公钥
+    pub market: Pubkey,
+    /// 数值市场中选择的选项
+    pub outcome: u8,
+    /// 是否已领取罚没补偿
+    pub compensation_claimed: bool,
 }
 
 impl Bet {
@@ -1054,7 +6917,570 @@ impl Bet {
                           8 +  // amount
                           8 +  // created_at
                           1 +  // is_settled
-                          1;   // is_winner
+                          1 +  // is_winner
+                          32 + // mint
+                          32 + // market
+                          1 +  // outcome
+                          1;   // compensation_claimed
+}
+
+/// 结果裁定人集合账户数据结构
+#[account]
+pub struct ResolverSet {
+    /// 关联的赛事
+    pub tournament: Pubkey,
+    /// 裁定人公钥列表
+    pub resolvers: Vec<Pubkey>,
+    /// 完成轮次所需的一致票数
+    pub threshold: u8,
+    /// 提交期时长(秒)，从轮次首个承诺开始计算
+    pub commit_period: i64,
+    /// 揭示期时长(秒)，紧接提交期
+    pub reveal_period: i64,
+    /// 提交承诺所需的最低matchp质押，未揭示时被罚没
+    pub stake_amount: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl ResolverSet {
+    /// 裁定人集合账户数据大小
+    pub const LEN: usize = 32 + // tournament
+                          4 + 32 * MAX_RESOLVERS + // resolvers
+                          1 +  // threshold
+                          8 +  // commit_period
+                          8 +  // reveal_period
+                          8 +  // stake_amount
+                          1;   // bump
+}
+
+/// 裁定人质押账户数据结构
+#[account]
+pub struct ResolverStake {
+    /// 关联的赛事
+    pub tournament: Pubkey,
+    /// 裁定人
+    pub resolver: Pubkey,
+    /// 质押的matchp数量
+    pub amount: u64,
+    /// 尚未揭示的承诺数量，大于0时质押被锁定
+    pub pending_reveals: u32,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl ResolverStake {
+    /// 裁定人质押账户数据大小
+    pub const LEN: usize = 32 + // tournament
+                          32 + // resolver
+                          8 +  // amount
+                          4 +  // pending_reveals
+                          1;   // bump
+}
+
+/// 裁定人的一张结果投票
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ResolverVote {
+    /// 投票的裁定人
+    pub resolver: Pubkey,
+    /// 承诺值hash(裁定人, 轮次, 获胜队伍, salt)
+    pub commitment: [u8; 32],
+    /// 揭示的获胜队伍，揭示前为默认值
+    pub team: Pubkey,
+    /// 是否已揭示
+    pub is_revealed: bool,
+    /// 是否已因未揭示被罚没
+    pub is_penalized: bool,
+}
+
+impl ResolverVote {
+    /// 计算承诺值，绑定裁定人和轮次防止承诺被其他裁定人复制或跨轮次重放
+    pub fn commitment_for(resolver: &Pubkey, round: &Pubkey, team: &Pubkey, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[resolver.as_ref(), round.as_ref(), team.as_ref(), salt]).to_bytes()
+    }
+}
+
+/// 轮次结果投票记录账户数据结构
+#[account]
+pub struct RoundResolution {
+    /// 关联的轮次
+    pub round: Pubkey,
+    /// 提交期截止时间
+    pub commit_deadline: i64,
+    /// 揭示期截止时间
+    pub reveal_deadline: i64,
+    /// 已提交的投票
+    pub votes: Vec<ResolverVote>,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl RoundResolution {
+    /// 投票记录账户数据大小
+    pub const LEN: usize = 32 + // round
+                          8 +  // commit_deadline
+                          8 +  // reveal_deadline
+                          4 + (32 + 32 + 32 + 1 + 1) * MAX_RESOLVERS + // votes
+                          1;   // bump
+}
+
+/// 预言机结果布局
+/// 外部预言机程序的结果账户需以Anchor账户格式存储，名为OracleResult且以下列字段开头
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct OracleResult {
+    /// 关联的轮次
+    pub round: Pubkey,
+    /// 获胜队伍
+    pub winner_team: Pubkey,
+    /// 最近更新时间
+    pub updated_at: i64,
+    /// 结果发布者
+    pub authority: Pubkey,
+}
+
+impl OracleResult {
+    /// 从预言机账户读取结果，校验所有者、账户类型标识和发布者
+    pub fn load(account: &AccountInfo, oracle_program: &Pubkey, publisher: &Pubkey) -> Result<Self> {
+        // 结果账户必须由指定的预言机程序拥有
+        require!(
+            account.owner == oracle_program,
+            TournamentError::InvalidOracleOwner
+        );
+        
+        let data = account.try_borrow_data()?;
+        require!(data.len() >= 8, TournamentError::InvalidOracleResult);
+        // 校验Anchor账户类型标识
+        let discriminator = hash(b"account:OracleResult").to_bytes();
+        require!(
+            data[..8] == discriminator[..8],
+            TournamentError::InvalidOracleResult
+        );
+        
+        let result = OracleResult::deserialize(&mut &data[8..])
+            .map_err(|_| error!(TournamentError::InvalidOracleResult))?;
+        // 结果必须由赛事指定的发布者发布
+        require!(
+            result.authority == *publisher,
+            TournamentError::InvalidOraclePublisher
+        );
+        Ok(result)
+    }
+}
+
+/// 轮次结果争议账户数据结构
+#[account]
+pub struct Dispute {
+    /// 关联的赛事
+    pub tournament: Pubkey,
+    /// 关联的轮次
+    pub round: Pubkey,
+    /// 争议发起人
+    pub challenger: Pubkey,
+    /// 原获胜队伍
+    pub original_team: Pubkey,
+    /// 发起人提出的获胜队伍
+    pub proposed_team: Pubkey,
+    /// 保证金代币铸造器
+    pub bond_mint: Pubkey,
+    /// 保证金数量
+    pub bond_amount: u64,
+    /// 发起时间
+    pub created_at: i64,
+    /// 是否已裁决
+    pub is_resolved: bool,
+    /// 是否推翻了原结果
+    pub is_overturned: bool,
+    /// 代币投票截止时间，未开启治理时为0
+    pub vote_deadline: i64,
+    /// 发起争议时赛事的法定票数，为0时由管理员仲裁
+    pub quorum: u64,
+    /// 支持推翻的加权票数
+    pub overturn_votes: u64,
+    /// 支持维持的加权票数
+    pub uphold_votes: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Dispute {
+    /// 争议账户数据大小
+    pub const LEN: usize = 32 + // tournament
+                          32 + // round
+                          32 + // challenger
+                          32 + // original_team
+                          32 + // proposed_team
+                          32 + // bond_mint
+                          8 +  // bond_amount
+                          8 +  // created_at
+                          1 +  // is_resolved
+                          1 +  // is_overturned
+                          8 +  // vote_deadline
+                          8 +  // quorum
+                          8 +  // overturn_votes
+                          8 +  // uphold_votes
+                          1;   // bump
+}
+
+/// 争议投票记录账户数据结构
+#[account]
+pub struct DisputeVote {
+    /// 关联的争议
+    pub dispute: Pubkey,
+    /// 投票人
+    pub voter: Pubkey,
+    /// 锁定的vote数量，即投票权重
+    pub amount: u64,
+    /// 是否支持推翻原结果
+    pub overturn: bool,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl DisputeVote {
+    /// 争议投票记录账户数据大小
+    pub const LEN: usize = 32 + // dispute
+                          32 + // voter
+                          8 +  // amount
+                          1 +  // overturn
+                          1;   // bump
+}
+
+/// 数值市场类型
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MarketKind {
+    /// 大小盘：结果(如总分)高于盘口为"大"，否则为"小"
+    OverUnder,
+    /// 胜分差区间：结果落入的区间即为获胜选项
+    MarginBuckets,
+    /// 让分盘：热门方胜分差超过让分为热门方赢盘，否则冷门方赢盘
+    Handicap,
+}
+
+/// 权限角色，合约管理员默认拥有全部角色
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// 全局管理员：管理下注代币白名单、水龙头地址、罚没配置和除全局管理员外的角色授予
+    Admin,
+    /// 赛事运营：白名单创建模式下可以创建赛事
+    Operator,
+    /// 争议仲裁员：仲裁争议，不参与提交或揭示轮次结果，轮次结果仍由赛事方和赛事裁定人提交
+    Resolver,
+    /// 国库管理员：提取国库资金
+    TreasuryManager,
+    /// 暂停管理员：暂停和恢复下注
+    Pauser,
+}
+
+/// 赛事创建模式，合约管理员不受限制
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CreationMode {
+    /// 任何人都可以创建赛事
+    Open,
+    /// 只有持有赛事运营角色的账户可以创建赛事
+    Allowlisted,
+    /// 创建赛事时必须质押不少于min_creation_stake的matchp
+    MinStake,
+}
+
+/// 数值市场账户数据结构
+#[account]
+pub struct Market {
+    /// 关联的赛事
+    pub tournament: Pubkey,
+    /// 关联的轮次
+    pub round: Pubkey,
+    /// 市场在轮次中的序号
+    pub market_index: u8,
+    /// 市场名称，如"总分大小"
+    pub name: String,
+    /// 市场类型
+    pub kind: MarketKind,
+    /// 大小盘盘口或让分盘让分，以半分为单位(如91表示45.5分)
+    pub line: i64,
+    /// 胜分差区间的上界(含)，严格递增
+    pub bucket_bounds: Vec<i64>,
+    /// 让分盘的热门方，其他市场为默认公钥
+    pub favorite_team: Pubkey,
+    /// 让分盘的冷门方，其他市场为默认公钥
+    pub underdog_team: Pubkey,
+    /// 选项数量
+    pub outcome_count: u8,
+    /// 各选项的下注总额
+    pub outcome_totals: [u64; MAX_MARKET_OUTCOMES],
+    /// 市场下注总额
+    pub total_bets: u64,
+    /// 是否已出结果
+    pub is_resolved: bool,
+    /// 是否走盘，走盘时所有下注退款
+    pub is_push: bool,
+    /// 报告的数值结果
+    pub result: i64,
+    /// 获胜选项
+    pub winning_outcome: u8,
+    /// 创建时间
+    pub created_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Market {
+    /// 市场账户数据大小
+    pub const LEN: usize = 32 + // tournament
+                          32 + // round
+                          1 +  // market_index
+                          32 + // name (max length)
+                          1 +  // kind
+                          8 +  // line
+                          4 + 8 * (MAX_MARKET_OUTCOMES - 1) + // bucket_bounds
+                          32 + // favorite_team
+                          32 + // underdog_team
+                          1 +  // outcome_count
+                          8 * MAX_MARKET_OUTCOMES + // outcome_totals
+                          8 +  // total_bets
+                          1 +  // is_resolved
+                          1 +  // is_push
+                          8 +  // result
+                          1 +  // winning_outcome
+                          8 +  // created_at
+                          1;   // bump
+
+    /// 根据数值结果计算获胜选项，走盘时返回None
+    pub fn outcome_for(&self, value: i64) -> Option<u8> {
+        match self.kind {
+            MarketKind::OverUnder => self.compare_line(value, OUTCOME_OVER, OUTCOME_UNDER),
+            MarketKind::Handicap => self.compare_line(value, OUTCOME_FAVORITE, OUTCOME_UNDERDOG),
+            MarketKind::MarginBuckets => Some(
+                self.bucket_bounds
+                    .iter()
+                    .position(|bound| value <= *bound)
+                    .unwrap_or(self.bucket_bounds.len()) as u8,
+            ),
+        }
+    }
+
+    /// 记录数值结果，确定获胜选项或走盘
+    pub fn resolve(&mut self, value: i64) {
+        let outcome = self.outcome_for(value);
+        self.result = value;
+        self.is_push = outcome.is_none();
+        self.winning_outcome = outcome.unwrap_or(0);
+        self.is_resolved = true;
+    }
+
+    /// 盘口以半分为单位，比较时将结果换算为半分；恰好等于盘口时走盘
+    fn compare_line(&self, value: i64, above: u8, below: u8) -> Option<u8> {
+        match value.saturating_mul(2).cmp(&self.line) {
+            std::cmp::Ordering::Greater => Some(above),
+            std::cmp::Ordering::Less => Some(below),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
+/// 质押份额账户数据结构
+#[account]
+pub struct StakePosition {
+    /// 关联的赛事
+    pub tournament: Pubkey,
+    /// 质押者
+    pub owner: Pubkey,
+    /// 质押份额
+    pub shares: u64,
+    /// 已结算到的累计奖励，按REWARD_PRECISION放大
+    pub reward_debt: u128,
+    /// 已结算但未领取的奖励
+    pub unclaimed_rewards: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl StakePosition {
+    /// 质押份额账户数据大小
+    pub const LEN: usize = 32 + // tournament
+                          32 + // owner
+                          8 +  // shares
+                          16 + // reward_debt
+                          8 +  // unclaimed_rewards
+                          1;   // bump
+
+    /// 将截至当前的奖励结算到未领取奖励中
+    pub fn harvest(&mut self, tournament: &Tournament) -> Result<()> {
+        let accrued = (self.shares as u128)
+            .checked_mul(tournament.acc_reward_per_share)
+            .ok_or(TournamentError::Overflow)?;
+        let pending = accrued
+            .checked_sub(self.reward_debt)
+            .ok_or(TournamentError::Overflow)?
+            / REWARD_PRECISION;
+        let pending = u64::try_from(pending)
+            .map_err(|_| TournamentError::Overflow)?;
+        self.unclaimed_rewards = self
+            .unclaimed_rewards
+            .checked_add(pending)
+            .ok_or(TournamentError::Overflow)?;
+        // 只扣除已结算的整数部分，保留精度余数
+        self.reward_debt += pending as u128 * REWARD_PRECISION;
+        Ok(())
+    }
+
+    /// 先结算奖励再调整份额，同步赛事的份额总数
+    pub fn set_shares(&mut self, tournament: &mut Tournament, shares: u64) -> Result<()> {
+        self.harvest(tournament)?;
+        tournament.reward_shares = tournament
+            .reward_shares
+            .checked_sub(self.shares)
+            .and_then(|total| total.checked_add(shares))
+            .ok_or(TournamentError::Overflow)?;
+        self.shares = shares;
+        self.reward_debt = (shares as u128)
+            .checked_mul(tournament.acc_reward_per_share)
+            .ok_or(TournamentError::Overflow)?;
+        Ok(())
+    }
+}
+
+/// vote分发配置账户数据结构
+#[account]
+pub struct VoteDistribution {
+    /// 关联的赛事
+    pub tournament: Pubkey,
+    /// wanzi兑换vote的比例(基点)，0表示关闭兑换
+    pub swap_rate_bps: u64,
+    /// 每位注册玩家可领取的vote数量，0表示关闭领取
+    pub player_allowance: u64,
+    /// 累计兑换出的vote数量
+    pub total_swapped: u64,
+    /// 累计领取的vote数量
+    pub total_claimed: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl VoteDistribution {
+    /// vote分发配置账户数据大小
+    pub const LEN: usize = 32 + // tournament
+                          8 +  // swap_rate_bps
+                          8 +  // player_allowance
+                          8 +  // total_swapped
+                          8 +  // total_claimed
+                          1;   // bump
+}
+
+/// 玩家注册记录账户数据结构
+#[account]
+pub struct PlayerRegistration {
+    /// 关联的赛事
+    pub tournament: Pubkey,
+    /// 玩家
+    pub player: Pubkey,
+    /// 是否已领取vote额度
+    pub has_claimed: bool,
+    /// 注册时间
+    pub registered_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl PlayerRegistration {
+    /// 玩家注册记录账户数据大小
+    pub const LEN: usize = 32 + // tournament
+                          32 + // player
+                          1 +  // has_claimed
+                          8 +  // registered_at
+                          1;   // bump
+}
+
+/// 下注挂单账户数据结构
+#[account]
+pub struct Listing {
+    /// 挂单的下注
+    pub bet: Pubkey,
+    /// 卖家
+    pub seller: Pubkey,
+    /// wanzi标价
+    pub price: u64,
+    /// 挂单时间
+    pub created_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Listing {
+    /// 挂单账户数据大小
+    pub const LEN: usize = 32 + // bet
+                          32 + // seller
+                          8 +  // price
+                          8 +  // created_at
+                          1;   // bump
+}
+
+/// 暂停状态变更事件
+#[event]
+pub struct PauseUpdated {
+    /// 被暂停的赛事，全局暂停时为空
+    pub tournament: Option<Pubkey>,
+    /// 是否暂停
+    pub paused: bool,
+    /// 操作者
+    pub pauser: Pubkey,
+}
+
+/// 赛事创建权限配置事件
+#[event]
+pub struct TournamentCreationConfigured {
+    /// 赛事创建模式
+    pub creation_mode: CreationMode,
+    /// 最低创建质押
+    pub min_creation_stake: u64,
+}
+
+/// 角色授予事件
+#[event]
+pub struct RoleGranted {
+    /// 角色
+    pub role: Role,
+    /// 角色持有者
+    pub holder: Pubkey,
+    /// 授予者
+    pub granted_by: Pubkey,
+}
+
+/// 角色撤销事件
+#[event]
+pub struct RoleRevoked {
+    /// 角色
+    pub role: Role,
+    /// 原角色持有者
+    pub holder: Pubkey,
+    /// 撤销者
+    pub revoked_by: Pubkey,
+}
+
+/// 提议转移合约管理员事件
+#[event]
+pub struct AuthorityProposed {
+    /// 当前管理员
+    pub authority: Pubkey,
+    /// 被提议的新管理员
+    pub pending_authority: Pubkey,
+}
+
+/// 新管理员接受转移事件
+#[event]
+pub struct AuthorityAccepted {
+    /// 原管理员
+    pub previous_authority: Pubkey,
+    /// 新管理员
+    pub authority: Pubkey,
+}
+
+/// 取消管理员转移事件
+#[event]
+pub struct AuthorityTransferCancelled {
+    /// 当前管理员
+    pub authority: Pubkey,
+    /// 被撤回的新管理员
+    pub pending_authority: Pubkey,
 }
 
 /// 错误码定义
@@ -1105,4 +7531,280 @@ pub enum TournamentError {
     /// 溢出错误
     #[msg("Overflow error")]
     Overflow,
+    /// 调用者不是下注的持有者
+    #[msg("Signer is not the owner of this bet")]
+    NotBetOwner,
+    /// 无效的下注接收者
+    #[msg("Invalid bet recipient")]
+    InvalidBetRecipient,
+    /// 无效的挂单价格
+    #[msg("Listing price must be greater than zero")]
+    InvalidListingPrice,
+    /// 轮次已出结果，不能再交易下注
+    #[msg("Round is completed, bet positions can no longer be traded")]
+    RoundClosedForTrading,
+    /// 下注与市场不匹配
+    #[msg("Bet does not belong to this market")]
+    InvalidBetMarket,
+    /// 无效的盘口
+    #[msg("Invalid market line")]
+    InvalidMarketLine,
+    /// 无效的区间设置
+    #[msg("Invalid market buckets")]
+    InvalidMarketBuckets,
+    /// 无效的市场选项
+    #[msg("Invalid market outcome")]
+    InvalidMarketOutcome,
+    /// 市场已出结果
+    #[msg("Market is already resolved")]
+    MarketAlreadyResolved,
+    /// 市场未出结果
+    #[msg("Market is not resolved")]
+    MarketNotResolved,
+    /// 市场类型不支持该操作
+    #[msg("Invalid market kind for this instruction")]
+    InvalidMarketKind,
+    /// 队伍比分已报告
+    #[msg("Team score is already reported")]
+    TeamAlreadyScored,
+    /// 队伍比分未报告
+    #[msg("Team score is not reported")]
+    TeamNotScored,
+    /// 该赛事需要裁定人投票完成轮次
+    #[msg("Round must be completed by resolver votes")]
+    ResolverVoteRequired,
+    /// 无效的裁定人集合
+    #[msg("Invalid resolver set")]
+    InvalidResolverSet,
+    /// 无效的裁定阈值
+    #[msg("Invalid resolver threshold")]
+    InvalidResolverThreshold,
+    /// 调用者不是裁定人
+    #[msg("Signer is not a resolver of this tournament")]
+    NotResolver,
+    /// 裁定人已投票
+    #[msg("Resolver has already voted on this round")]
+    ResolverAlreadyVoted,
+    /// 无效的争议期
+    #[msg("Invalid dispute period")]
+    InvalidDisputePeriod,
+    /// 无效的争议保证金
+    #[msg("Invalid dispute bond")]
+    InvalidDisputeBond,
+    /// 无效的保证金代币
+    #[msg("Invalid bond mint")]
+    InvalidBondMint,
+    /// 轮次存在未裁决的争议
+    #[msg("Round result is under dispute")]
+    RoundDisputed,
+    /// 争议期尚未结束
+    #[msg("Dispute window is still open")]
+    DisputeWindowOpen,
+    /// 争议期已结束
+    #[msg("Dispute window is closed")]
+    DisputeWindowClosed,
+    /// 争议已裁决
+    #[msg("Dispute is already resolved")]
+    DisputeAlreadyResolved,
+    /// 调用者不是仲裁人
+    #[msg("Signer is not the arbiter")]
+    NotArbiter,
+    /// 赛事未质押
+    #[msg("Tournament is not staked")]
+    TournamentNotStaked,
+    /// 无效的投票期
+    #[msg("Invalid vote period")]
+    InvalidVotePeriod,
+    /// 无效的投票数量
+    #[msg("Invalid vote amount")]
+    InvalidVoteAmount,
+    /// 该赛事的争议需要代币投票裁决
+    #[msg("Dispute must be resolved by token vote")]
+    GovernanceVoteRequired,
+    /// 该赛事未开启代币投票治理
+    #[msg("Token vote governance is not enabled")]
+    GovernanceNotEnabled,
+    /// 投票期已结束
+    #[msg("Voting period is closed")]
+    VotingClosed,
+    /// 投票期尚未结束
+    #[msg("Voting period is still open")]
+    VotingOpen,
+    /// 争议未裁决
+    #[msg("Dispute is not resolved")]
+    DisputeNotResolved,
+    /// 无效的提交期或揭示期
+    #[msg("Invalid commit or reveal period")]
+    InvalidResolverPeriod,
+    /// 裁定人质押不足
+    #[msg("Insufficient resolver stake")]
+    InsufficientResolverStake,
+    /// 裁定人有待揭示的承诺，质押被锁定
+    #[msg("Resolver stake is locked by pending reveals")]
+    ResolverStakeLocked,
+    /// 提交期已结束
+    #[msg("Commit phase is closed")]
+    CommitPhaseClosed,
+    /// 提交期尚未结束
+    #[msg("Commit phase is still open")]
+    CommitPhaseOpen,
+    /// 揭示期已结束
+    #[msg("Reveal phase is closed")]
+    RevealPhaseClosed,
+    /// 揭示期尚未结束
+    #[msg("Reveal phase is still open")]
+    RevealPhaseOpen,
+    /// 未找到裁定人的承诺
+    #[msg("Commitment not found")]
+    CommitmentNotFound,
+    /// 承诺已揭示
+    #[msg("Commitment already revealed")]
+    AlreadyRevealed,
+    /// 揭示内容与承诺不一致
+    #[msg("Reveal does not match commitment")]
+    InvalidReveal,
+    /// 该承诺不能被罚没
+    #[msg("Commitment cannot be penalized")]
+    NotPenalizable,
+    /// 无效的预言机配置
+    #[msg("Invalid oracle configuration")]
+    InvalidOracleConfig,
+    /// 该赛事需要预言机结果
+    #[msg("Oracle result account is required")]
+    OracleResultRequired,
+    /// 预言机结果账户的所有者不是指定的预言机程序
+    #[msg("Oracle result account has the wrong owner")]
+    InvalidOracleOwner,
+    /// 无效的预言机结果
+    #[msg("Invalid oracle result")]
+    InvalidOracleResult,
+    /// 预言机结果已过期
+    #[msg("Oracle result is stale")]
+    StaleOracleResult,
+    /// 无效的罚没配置
+    #[msg("Invalid slashing configuration")]
+    InvalidSlashConfig,
+    /// 该赛事未开启罚没
+    #[msg("Slashing is not enabled")]
+    SlashingNotEnabled,
+    /// 该轮次已罚没
+    #[msg("Round has already been slashed")]
+    RoundAlreadySlashed,
+    /// 该轮次不满足罚没条件
+    #[msg("Round is not slashable")]
+    RoundNotSlashable,
+    /// 该轮次没有补偿
+    #[msg("No compensation for this round")]
+    NoCompensation,
+    /// 该下注已领取补偿
+    #[msg("Compensation already claimed")]
+    CompensationAlreadyClaimed,
+    /// 尚未超过裁定期限
+    #[msg("Resolution deadline has not passed")]
+    ResolutionDeadlineNotPassed,
+    /// 轮次未过期
+    #[msg("Round is not expired")]
+    RoundNotExpired,
+    /// vote代币铸造器不属于该赛事
+    #[msg("Vote mint does not belong to this tournament")]
+    InvalidVoteMint,
+    /// 赎回后vote供应量将超过剩余质押
+    #[msg("Unstaking would leave vote supply unbacked")]
+    UnbackedVoteSupply,
+    /// vote分发未开启
+    #[msg("Vote distribution is disabled")]
+    VoteDistributionDisabled,
+    /// 玩家已领取vote额度
+    #[msg("Vote allowance already claimed")]
+    AllowanceAlreadyClaimed,
+    /// 无效的手续费配置
+    #[msg("Invalid fee configuration")]
+    InvalidFeeConfig,
+    /// 收取手续费需要提供奖励池
+    #[msg("Rewards vault is required to collect fees")]
+    RewardsVaultRequired,
+    /// 没有可领取的奖励
+    #[msg("No rewards to claim")]
+    NoRewards,
+    /// 无效的委托者
+    #[msg("Invalid delegator")]
+    InvalidDelegator,
+    /// 转账扣除手续费后池子没有收到代币
+    #[msg("No tokens were received after transfer fees")]
+    NothingReceived,
+    /// 无效的下注代币铸造器
+    #[msg("Invalid currency mint")]
+    InvalidCurrencyMint,
+    /// 下注代币不在白名单中
+    #[msg("Currency is not allowed")]
+    CurrencyNotAllowed,
+    /// 下注代币已在白名单中
+    #[msg("Currency is already allowed")]
+    CurrencyAlreadyAllowed,
+    /// 下注代币白名单已满
+    #[msg("Currency allowlist is full")]
+    CurrencyAllowlistFull,
+    /// SOL赛事需要提供SOL托管账户
+    #[msg("SOL vault is required for SOL tournaments")]
+    SolVaultRequired,
+    /// 代币赛事需要提供下注代币账户
+    #[msg("Currency token accounts are required")]
+    CurrencyAccountsRequired,
+    /// 无效的wanzi代币铸造器
+    #[msg("Invalid wanzi mint")]
+    InvalidWanziMint,
+    /// 无效的matchp代币铸造器
+    #[msg("Invalid matchp mint")]
+    InvalidMatchpMint,
+    /// 代币账户的铸造器不匹配
+    #[msg("Token account mint mismatch")]
+    InvalidTokenMint,
+    /// 代币账户的所有者不匹配
+    #[msg("Token account owner mismatch")]
+    InvalidTokenOwner,
+    /// 无效的待接受管理员
+    #[msg("Invalid pending authority")]
+    InvalidPendingAuthority,
+    /// 没有进行中的管理员转移
+    #[msg("No pending authority transfer")]
+    NoPendingAuthority,
+    /// 调用者不是待接受的新管理员
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    /// 调用者没有所需的角色
+    #[msg("Signer does not hold the required role")]
+    MissingRole,
+    /// 合约已全局暂停
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    /// 赛事已暂停
+    #[msg("Tournament is paused")]
+    TournamentPaused,
+    /// 创建赛事的质押低于最低要求
+    #[msg("Stake is below the minimum required to create a tournament")]
+    CreationStakeTooLow,
+    /// 赛事有未裁决的争议
+    #[msg("Dispute settings cannot change while a dispute is open")]
+    DisputeInProgress,
+    /// 结果被推翻的罚没缺少争议发起人账户
+    #[msg("Dispute and challenger token account are required to slash an overturned round")]
+    ChallengerAccountRequired,
+    /// 全局管理员角色只能由合约管理员授予或撤销
+    #[msg("Only the contract authority can grant or revoke the admin role")]
+    AdminRoleRestricted,
+    /// 预言机配置已设置，不能再修改
+    #[msg("Oracle configuration is permanent once set")]
+    OracleConfigLocked,
+    /// 预言机结果不是由指定的发布者发布
+    #[msg("Oracle result was not published by the configured publisher")]
+    InvalidOraclePublisher,
+    /// 赛事还有未了结的轮次
+    #[msg("Tournament has unresolved rounds, open disputes or pending slashes")]
+    UnsettledRounds,
+    /// 轮次已过期或超过裁定期限
+    #[msg("Round has expired or passed its resolution deadline")]
+    ResolutionDeadlinePassed,
+    /// 赛事有下注或轮次已出结果，手续费不能再修改
+    #[msg("Fees cannot change after bets are placed or a round is completed")]
+    FeesLocked,
 }
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, MintTo, TransferChecked};
 
 declare_id!("TokenFaucetProgram1111111111111111111111111");
 
@@ -40,7 +40,7 @@ pub mod token_faucet {
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program.clone(), cpi_accounts);
-        token::mint_to(cpi_ctx, amount)?;
+        token_interface::mint_to(cpi_ctx, amount)?;
 
         // 铸造matchp代币到管理员账户
         let cpi_accounts = MintTo {
@@ -49,7 +49,7 @@ pub mod token_faucet {
             authority: ctx.accounts.authority.to_account_info(),
         };
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::mint_to(cpi_ctx, amount)?;
+        token_interface::mint_to(cpi_ctx, amount)?;
 
         Ok(())
     }
@@ -70,29 +70,85 @@ pub mod token_faucet {
         let amount = 100 * 10u64.pow(9);
 
         // 从管理员账户转移wanzi代币到用户账户
-        let cpi_accounts = token::Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.authority_wanzi_token.to_account_info(),
+            mint: ctx.accounts.wanzi_mint.to_account_info(),
             to: ctx.accounts.user_wanzi_token.to_account_info(),
             authority: ctx.accounts.authority.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program.clone(), cpi_accounts);
-        token::transfer(cpi_ctx, amount)?;
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.wanzi_mint.decimals)?;
 
         // 从管理员账户转移matchp代币到用户账户
-        let cpi_accounts = token::Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.authority_matchp_token.to_account_info(),
+            mint: ctx.accounts.matchp_mint.to_account_info(),
             to: ctx.accounts.user_matchp_token.to_account_info(),
             authority: ctx.accounts.authority.to_account_info(),
         };
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, amount)?;
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.matchp_mint.decimals)?;
 
         // 记录用户已领取，防止重复领取
         faucet.claimed_users.push(ctx.accounts.user.key());
 
         Ok(())
     }
+
+    /// 提议转移水龙头管理员
+    /// 新管理员需要调用accept_authority确认后才会生效
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        require!(new_authority != Pubkey::default(), FaucetError::InvalidPendingAuthority);
+        let faucet = &mut ctx.accounts.faucet;
+        faucet.pending_authority = new_authority;
+
+        emit!(AuthorityProposed {
+            authority: faucet.authority,
+            pending_authority: new_authority,
+        });
+        Ok(())
+    }
+
+    /// 接受水龙头管理员转移
+    /// 由被提议的新管理员签名，确认后原管理员失去权限
+    pub fn accept_authority(
+        ctx: Context<AcceptAuthority>,
+    ) -> Result<()> {
+        let faucet = &mut ctx.accounts.faucet;
+        let previous_authority = faucet.authority;
+        faucet.authority = ctx.accounts.pending_authority.key();
+        faucet.pending_authority = Pubkey::default();
+
+        emit!(AuthorityAccepted {
+            previous_authority,
+            authority: faucet.authority,
+        });
+        Ok(())
+    }
+
+    /// 取消水龙头管理员转移
+    /// 当前管理员撤回尚未被接受的提议
+    pub fn cancel_authority_transfer(
+        ctx: Context<CancelAuthorityTransfer>,
+    ) -> Result<()> {
+        let faucet = &mut ctx.accounts.faucet;
+        require!(
+            faucet.pending_authority != Pubkey::default(),
+            FaucetError::NoPendingAuthority
+        );
+        let pending_authority = faucet.pending_authority;
+        faucet.pending_authority = Pubkey::default();
+
+        emit!(AuthorityTransferCancelled {
+            authority: faucet.authority,
+            pending_authority,
+        });
+        Ok(())
+    }
 }
 
 /// 初始化指令所需的账户结构
@@ -113,21 +169,21 @@ pub struct Initialize<'info> {
     pub faucet: Account<'info, Faucet>,
 
     /// wanzi代币铸造器
-    pub wanzi_mint: Box<Account<'info, Mint>>,
+    pub wanzi_mint: Box<InterfaceAccount<'info, Mint>>,
     /// matchp代币铸造器
-    pub matchp_mint: Box<Account<'info, Mint>>,
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// 管理员的wanzi代币账户
     #[account(mut)]
-    pub authority_wanzi_token: Box<Account<'info, TokenAccount>>,
+    pub authority_wanzi_token: Box<InterfaceAccount<'info, TokenAccount>>,
     /// 管理员的matchp代币账户
     #[account(mut)]
-    pub authority_matchp_token: Box<Account<'info, TokenAccount>>,
+    pub authority_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// 系统程序
     pub system_program: Program<'info, System>,
     /// 代币程序
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     /// 租金系统变量
     pub rent: Sysvar<'info, Rent>,
 }
@@ -147,19 +203,19 @@ pub struct MintTokens<'info> {
     pub faucet: Account<'info, Faucet>,
 
     /// wanzi代币铸造器
-    pub wanzi_mint: Box<Account<'info, Mint>>,
+    pub wanzi_mint: Box<InterfaceAccount<'info, Mint>>,
     /// matchp代币铸造器
-    pub matchp_mint: Box<Account<'info, Mint>>,
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// 管理员的wanzi代币账户
     #[account(mut)]
-    pub authority_wanzi_token: Box<Account<'info, TokenAccount>>,
+    pub authority_wanzi_token: Box<InterfaceAccount<'info, TokenAccount>>,
     /// 管理员的matchp代币账户
     #[account(mut)]
-    pub authority_matchp_token: Box<Account<'info, TokenAccount>>,
+    pub authority_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// 代币程序
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 /// 领取代币指令所需的账户结构
@@ -180,22 +236,77 @@ pub struct ClaimTokens<'info> {
     /// CHECK: 这是要领取代币的用户
     pub user: AccountInfo<'info>,
 
+    /// wanzi代币铸造器
+    #[account(address = faucet.wanzi_mint)]
+    pub wanzi_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// matchp代币铸造器
+    #[account(address = faucet.matchp_mint)]
+    pub matchp_mint: Box<InterfaceAccount<'info, Mint>>,
+
     /// 用户的wanzi代币账户
     #[account(mut)]
-    pub user_wanzi_token: Box<Account<'info, TokenAccount>>,
+    pub user_wanzi_token: Box<InterfaceAccount<'info, TokenAccount>>,
     /// 用户的matchp代币账户
     #[account(mut)]
-    pub user_matchp_token: Box<Account<'info, TokenAccount>>,
+    pub user_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// 管理员的wanzi代币账户
     #[account(mut)]
-    pub authority_wanzi_token: Box<Account<'info, TokenAccount>>,
+    pub authority_wanzi_token: Box<InterfaceAccount<'info, TokenAccount>>,
     /// 管理员的matchp代币账户
     #[account(mut)]
-    pub authority_matchp_token: Box<Account<'info, TokenAccount>>,
+    pub authority_matchp_token: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// 代币程序
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 提议转移管理员指令所需的账户结构
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    /// 当前管理员，必须是签名者
+    pub authority: Signer<'info>,
+
+    /// 水龙头账户，验证调用者是管理员
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"faucet"],
+        bump
+    )]
+    pub faucet: Account<'info, Faucet>,
+}
+
+/// 接受管理员转移指令所需的账户结构
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    /// 被提议的新管理员，必须是签名者
+    pub pending_authority: Signer<'info>,
+
+    /// 水龙头账户，验证调用者是被提议的新管理员
+    #[account(
+        mut,
+        constraint = faucet.pending_authority == pending_authority.key() @ FaucetError::NotPendingAuthority,
+        seeds = [b"faucet"],
+        bump
+    )]
+    pub faucet: Account<'info, Faucet>,
+}
+
+/// 取消管理员转移指令所需的账户结构
+#[derive(Accounts)]
+pub struct CancelAuthorityTransfer<'info> {
+    /// 当前管理员，必须是签名者
+    pub authority: Signer<'info>,
+
+    /// 水龙头账户，验证调用者是管理员
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"faucet"],
+        bump
+    )]
+    pub faucet: Account<'info, Faucet>,
 }
 
 /// 水龙头合约数据结构
@@ -207,13 +318,42 @@ pub struct Faucet {
     pub wanzi_mint: Pubkey,
     /// matchp代币铸造器地址
     pub matchp_mint: Pubkey,
+    /// 待接受的新管理员，没有进行中的转移时为默认公钥
+    pub pending_authority: Pubkey,
     /// 已领取代币的用户列表
     pub claimed_users: Vec<Pubkey>,
 }
 
 impl Faucet {
     /// 水龙头账户数据大小，预留1000个用户空间
-    pub const LEN: usize = 32 + 32 + 32 + 4 + (32 * 1000); // 预留1000个用户的空间
+    pub const LEN: usize = 32 + 32 + 32 + 32 + 4 + (32 * 1000); // 预留1000个用户的空间
+}
+
+/// 提议转移水龙头管理员事件
+#[event]
+pub struct AuthorityProposed {
+    /// 当前管理员
+    pub authority: Pubkey,
+    /// 被提议的新管理员
+    pub pending_authority: Pubkey,
+}
+
+/// 新管理员接受转移事件
+#[event]
+pub struct AuthorityAccepted {
+    /// 原管理员
+    pub previous_authority: Pubkey,
+    /// 新管理员
+    pub authority: Pubkey,
+}
+
+/// 取消管理员转移事件
+#[event]
+pub struct AuthorityTransferCancelled {
+    /// 当前管理员
+    pub authority: Pubkey,
+    /// 被撤回的新管理员
+    pub pending_authority: Pubkey,
 }
 
 /// 错误码定义
@@ -222,4 +362,13 @@ pub enum FaucetError {
     /// 用户已经领取过代币，不能重复领取
     #[msg("User has already claimed tokens")]
     AlreadyClaimed,
+    /// 无效的待接受管理员
+    #[msg("Invalid pending authority")]
+    InvalidPendingAuthority,
+    /// 没有进行中的管理员转移
+    #[msg("No pending authority transfer")]
+    NoPendingAuthority,
+    /// 调用者不是待接受的新管理员
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
 } 
\ No newline at end of file
@@ -1,9 +1,11 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { TournamentBettingSystem } from "../target/types/tournament_betting_system";
-import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress, createMint, getOrCreateAssociatedTokenAccount, mintTo, setAuthority, getMint } from "@solana/spl-token";
+import { MockOracle } from "../target/types/mock_oracle";
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAssociatedTokenAddressSync, createMint, getOrCreateAssociatedTokenAccount, mintTo, setAuthority, getMint, getAccount, transfer, TOKEN_2022_PROGRAM_ID, ExtensionType, getMintLen, createInitializeTransferFeeConfigInstruction, createInitializeMintInstruction, NATIVE_MINT } from "@solana/spl-token";
 import { expect } from "chai";
+import { createHash, randomBytes } from "crypto";
 
 describe("tournament-betting-system", () => {
   const provider = anchor.AnchorProvider.env();
@@ -73,6 +75,470 @@ describe("tournament-betting-system", () => {
     );
   });
 
+  // 合约状态账户，全局只有一个
+  const [statePda] = PublicKey.findProgramAddressSync([Buffer.from("state")], program.programId);
+
+  const pda = (...seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+  // 生成并空投SOL的新账户
+  async function fundedKeypair(): Promise<anchor.web3.Keypair> {
+    const keypair = anchor.web3.Keypair.generate();
+    const signature = await provider.connection.requestAirdrop(keypair.publicKey, 10 * LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(signature);
+    return keypair;
+  }
+
+  // 断言交易因指定的错误码失败
+  async function expectError(promise: Promise<unknown>, code: string) {
+    let error: any = null;
+    try {
+      await promise;
+    } catch (e) {
+      error = e;
+    }
+    expect(error, `应当因 ${code} 失败`).to.not.equal(null);
+    const actual = error.error?.errorCode?.code ?? error.toString();
+    expect(actual).to.contain(code);
+  }
+
+  // 代币账户余额，账户不存在时为0
+  async function balance(account: PublicKey): Promise<number> {
+    const info = await provider.connection.getAccountInfo(account);
+    if (!info) {
+      return 0;
+    }
+    return Number((await getAccount(provider.connection, account, undefined, info.owner)).amount);
+  }
+
+  // 获取或创建代币账户，wanzi、matchp和测试下注代币由authority铸造
+  async function tokenAccount(mint: PublicKey, owner: PublicKey, amount = 0, tokenProgram = TOKEN_PROGRAM_ID): Promise<PublicKey> {
+    const account = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mint,
+      owner,
+      false,
+      undefined,
+      undefined,
+      tokenProgram
+    );
+    if (amount > 0) {
+      await mintTo(provider.connection, authority, mint, account.address, authority, amount, [], undefined, tokenProgram);
+    }
+    return account.address;
+  }
+
+  // 铸造器所属的代币程序
+  async function mintProgram(mint: PublicKey): Promise<PublicKey> {
+    return (await provider.connection.getAccountInfo(mint)).owner;
+  }
+
+  // 合约状态账户只初始化一次，authority为合约管理员
+  async function ensureState() {
+    const signature = await provider.connection.requestAirdrop(authority.publicKey, 10 * LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(signature);
+    if (await provider.connection.getAccountInfo(statePda)) {
+      return;
+    }
+    await program.methods
+      .initialize(new PublicKey("TokenFaucetProgram1111111111111111111111111"))
+      .accounts({
+        authority: authority.publicKey,
+        state: statePda,
+        wanziMint: wanziMint.publicKey,
+        matchpMint: matchpMint.publicKey,
+        voteMint: voteMint.publicKey,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      } as any)
+      .signers([authority])
+      .rpc();
+  }
+
+  // 管理员将代币加入下注代币白名单，已在白名单中时跳过
+  async function allowCurrency(mint: PublicKey) {
+    const currencyAllowlist = pda(Buffer.from("currency_allowlist"));
+    if (await provider.connection.getAccountInfo(currencyAllowlist)) {
+      const { mints } = await program.account.currencyAllowlist.fetch(currencyAllowlist);
+      if (mints.some((allowed) => allowed.equals(mint))) {
+        return;
+      }
+    }
+    await program.methods
+      .addCurrency(mint)
+      .accounts({
+        authority: authority.publicKey,
+        state: statePda,
+        adminRole: null,
+        currencyAllowlist,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([authority])
+      .rpc();
+  }
+
+  // 角色序号与合约中Role枚举一致
+  const ROLES = { admin: 0, operator: 1, resolver: 2, treasuryManager: 3, pauser: 4 };
+  type RoleName = keyof typeof ROLES;
+
+  const rolePda = (role: RoleName, holder: PublicKey) =>
+    pda(Buffer.from("role"), Buffer.from([ROLES[role]]), holder.toBuffer());
+
+  // 授予角色，默认由合约管理员授予，全局管理员授予时需提供其角色账户
+  function grantRole(role: RoleName, holder: PublicKey, signer = authority, adminRole: PublicKey | null = null) {
+    return program.methods
+      .grantRole({ [role]: {} } as any, holder)
+      .accounts({
+        authority: signer.publicKey,
+        state: statePda,
+        adminRole,
+        roleAssignment: rolePda(role, holder),
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([signer])
+      .rpc();
+  }
+
+  function revokeRole(role: RoleName, holder: PublicKey, signer = authority, adminRole: PublicKey | null = null) {
+    return program.methods
+      .revokeRole({ [role]: {} } as any, holder)
+      .accounts({
+        authority: signer.publicKey,
+        state: statePda,
+        adminRole,
+        roleAssignment: rolePda(role, holder),
+      } as any)
+      .signers([signer])
+      .rpc();
+  }
+
+  // 创建赛事并返回赛事PDA，默认以wanzi为下注代币；质押matchp的赛事改用赛事vote代币下注
+  // 白名单创建模式下赛事运营需提供其角色账户
+  async function createTournament(
+    creator: anchor.web3.Keypair,
+    name: string,
+    stake = 0,
+    currencyMint: PublicKey = wanziMint.publicKey,
+    operatorRole: PublicKey | null = null
+  ): Promise<PublicKey> {
+    const tournament = pda(Buffer.from("tournament"), creator.publicKey.toBuffer());
+    const tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+    const isWanzi = currencyMint.equals(wanziMint.publicKey);
+    await program.methods
+      .createTournament(name, new anchor.BN(stake))
+      .accounts({
+        authority: creator.publicKey,
+        tournament,
+        state: statePda,
+        operatorRole,
+        currencyMint,
+        currencyAllowlist: isWanzi ? null : pda(Buffer.from("currency_allowlist")),
+        matchpMint: matchpMint.publicKey,
+        voteMint: tournamentVoteMint,
+        authorityMatchpToken: await tokenAccount(matchpMint.publicKey, creator.publicKey, stake),
+        authorityVoteToken: getAssociatedTokenAddressSync(tournamentVoteMint, creator.publicKey),
+        tournamentMatchpToken: pda(Buffer.from("tournament_matchp"), tournament.toBuffer()),
+        tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+        stakePosition: pda(Buffer.from("stake_position"), tournament.toBuffer(), creator.publicKey.toBuffer()),
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: await mintProgram(currencyMint),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      } as any)
+      .signers([creator])
+      .rpc();
+    return tournament;
+  }
+
+  async function createRound(creator: anchor.web3.Keypair, tournament: PublicKey, roundNumber: number): Promise<PublicKey> {
+    const round = pda(Buffer.from("round"), tournament.toBuffer(), Buffer.from([roundNumber]));
+    await program.methods
+      .createTournamentRound(`第${roundNumber}轮`, roundNumber)
+      .accounts({
+        authority: creator.publicKey,
+        tournament,
+        round,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      } as any)
+      .signers([creator])
+      .rpc();
+    return round;
+  }
+
+  async function createTeam(creator: anchor.web3.Keypair, tournament: PublicKey, round: PublicKey, name: string): Promise<PublicKey> {
+    const team = pda(Buffer.from("team"), round.toBuffer(), Buffer.from(name));
+    await program.methods
+      .createTeam(name)
+      .accounts({
+        authority: creator.publicKey,
+        tournament,
+        round,
+        team,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      } as any)
+      .signers([creator])
+      .rpc();
+    return team;
+  }
+
+  // 赛事方完成轮次，指定获胜队伍；配置了预言机的赛事需提供预言机结果账户
+  async function completeRound(
+    creator: anchor.web3.Keypair,
+    tournament: PublicKey,
+    round: PublicKey,
+    winnerTeam: PublicKey,
+    oracleResult: PublicKey | null = null
+  ) {
+    await program.methods
+      .completeRound(winnerTeam)
+      .accounts({
+        authority: creator.publicKey,
+        tournament,
+        round,
+        winnerTeam,
+        oracleResult,
+      } as any)
+      .signers([creator])
+      .rpc();
+  }
+
+  // 在wanzi赛事中下注，质押赛事使用赛事vote代币；返回下注账户和下注NFT的铸造器
+  async function placeBet(user: anchor.web3.Keypair, tournament: PublicKey, round: PublicKey, team: PublicKey, amount: number) {
+    const bet = anchor.web3.Keypair.generate();
+    const tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+    const { isStaked, currencyMint } = await program.account.tournament.fetch(tournament);
+    const currencyTokenProgram = await mintProgram(currencyMint);
+    // SOL赛事的lamports转入托管账户，无需代币账户
+    const isSol = !isStaked && currencyMint.equals(NATIVE_MINT);
+    const useToken = !isStaked && !isSol;
+    await program.methods
+      .placeBet(new anchor.BN(amount))
+      .accounts({
+        user: user.publicKey,
+        tournament,
+        state: statePda,
+        round,
+        team,
+        bet: bet.publicKey,
+        userCurrencyToken: !useToken ? null : getAssociatedTokenAddressSync(currencyMint, user.publicKey, false, currencyTokenProgram),
+        userVoteToken: await tokenAccount(tournamentVoteMint, user.publicKey),
+        currencyVault: !useToken ? null : pda(Buffer.from("currency_vault"), tournament.toBuffer()),
+        solVault: isSol ? pda(Buffer.from("sol_vault"), tournament.toBuffer()) : null,
+        tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+        currencyMint: !useToken ? null : currencyMint,
+        voteMint: tournamentVoteMint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram,
+        nftTokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      } as any)
+      .signers([user, bet])
+      .rpc();
+    return { bet: bet.publicKey, betMint: pda(Buffer.from("bet_mint"), bet.publicKey.toBuffer()) };
+  }
+
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  // 等待链上时间超过指定时间戳
+  async function waitForTimestamp(timestamp: number) {
+    for (;;) {
+      const slot = await provider.connection.getSlot();
+      const blockTime = await provider.connection.getBlockTime(slot);
+      if (blockTime !== null && blockTime > timestamp) {
+        return;
+      }
+      await sleep(500);
+    }
+  }
+
+  // 在数值市场中下注，代币选择与placeBet相同
+  async function placeMarketBet(user: anchor.web3.Keypair, tournament: PublicKey, round: PublicKey, market: PublicKey, amount: number, outcome: number) {
+    const bet = anchor.web3.Keypair.generate();
+    const tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+    const { isStaked } = await program.account.tournament.fetch(tournament);
+    await program.methods
+      .placeMarketBet(new anchor.BN(amount), outcome)
+      .accounts({
+        user: user.publicKey,
+        tournament,
+        state: statePda,
+        round,
+        market,
+        bet: bet.publicKey,
+        userCurrencyToken: isStaked ? null : getAssociatedTokenAddressSync(wanziMint.publicKey, user.publicKey),
+        userVoteToken: await tokenAccount(tournamentVoteMint, user.publicKey),
+        currencyVault: isStaked ? null : pda(Buffer.from("currency_vault"), tournament.toBuffer()),
+        solVault: null,
+        tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+        currencyMint: isStaked ? null : wanziMint.publicKey,
+        voteMint: tournamentVoteMint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
+        nftTokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      } as any)
+      .signers([user, bet])
+      .rpc();
+    return { bet: bet.publicKey, betMint: pda(Buffer.from("bet_mint"), bet.publicKey.toBuffer()) };
+  }
+
+  // 结算wanzi赛事的数值市场下注
+  async function settleMarketBet(
+    user: anchor.web3.Keypair,
+    tournament: PublicKey,
+    round: PublicKey,
+    market: PublicKey,
+    placed: { bet: PublicKey; betMint: PublicKey }
+  ) {
+    const tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+    await program.methods
+      .settleMarketBet()
+      .accounts({
+        tournament,
+        market,
+        round,
+        bet: placed.bet,
+        user: user.publicKey,
+        userBetNft: getAssociatedTokenAddressSync(placed.betMint, user.publicKey),
+        betMint: placed.betMint,
+        userCurrencyToken: getAssociatedTokenAddressSync(wanziMint.publicKey, user.publicKey),
+        userVoteToken: getAssociatedTokenAddressSync(tournamentVoteMint, user.publicKey),
+        currencyVault: pda(Buffer.from("currency_vault"), tournament.toBuffer()),
+        solVault: null,
+        tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+        rewardsVault: null,
+        currencyMint: wanziMint.publicKey,
+        voteMint: tournamentVoteMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
+        nftTokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([user])
+      .rpc();
+  }
+
+  // 缴纳保证金对轮次结果发起争议，返回争议账户
+  async function challengeResult(
+    challenger: anchor.web3.Keypair,
+    tournament: PublicKey,
+    round: PublicKey,
+    proposedTeam: PublicKey,
+    bondMint: PublicKey
+  ): Promise<PublicKey> {
+    const dispute = pda(Buffer.from("dispute"), round.toBuffer());
+    await program.methods
+      .challengeResult()
+      .accounts({
+        challenger: challenger.publicKey,
+        state: statePda,
+        tournament,
+        round,
+        proposedTeam,
+        dispute,
+        bondMint,
+        challengerBondToken: getAssociatedTokenAddressSync(bondMint, challenger.publicKey),
+        disputeBondVault: pda(Buffer.from("dispute_bond"), dispute.toBuffer()),
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      } as any)
+      .signers([challenger])
+      .rpc();
+    return dispute;
+  }
+
+  // 裁决争议所需的账户，维持原结果时保证金进入该代币的国库
+  function rulingAccounts(
+    tournament: PublicKey,
+    round: PublicKey,
+    originalTeam: PublicKey,
+    proposedTeam: PublicKey,
+    challenger: PublicKey,
+    bondMint: PublicKey,
+    payer: PublicKey
+  ) {
+    const dispute = pda(Buffer.from("dispute"), round.toBuffer());
+    return {
+      tournament,
+      round,
+      dispute,
+      originalTeam,
+      proposedTeam,
+      challenger,
+      disputeBondVault: pda(Buffer.from("dispute_bond"), dispute.toBuffer()),
+      challengerBondToken: getAssociatedTokenAddressSync(bondMint, challenger),
+      state: statePda,
+      treasury: pda(Buffer.from("treasury"), bondMint.toBuffer()),
+      payer,
+      bondMint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+  }
+
+  // 罚没赛事方质押，结果被推翻时需提供争议和发起人的matchp账户
+  async function slashStake(
+    caller: anchor.web3.Keypair,
+    tournament: PublicKey,
+    round: PublicKey,
+    dispute: PublicKey | null,
+    challengerMatchpToken: PublicKey | null,
+    tournamentMatchpToken = pda(Buffer.from("tournament_matchp"), tournament.toBuffer())
+  ) {
+    await program.methods
+      .slashStake()
+      .accounts({
+        caller: caller.publicKey,
+        state: statePda,
+        tournament,
+        round,
+        tournamentMatchpToken,
+        dispute,
+        challengerMatchpToken,
+        compensationVault: pda(Buffer.from("compensation"), round.toBuffer()),
+        treasury: pda(Buffer.from("treasury"), matchpMint.publicKey.toBuffer()),
+        matchpMint: matchpMint.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      } as any)
+      .signers([caller])
+      .rpc();
+  }
+
+  // 领取罚没补偿，未结算的下注由NFT持有者领取，已结算或退款的下注无需提供NFT
+  async function claimCompensation(
+    user: anchor.web3.Keypair,
+    tournament: PublicKey,
+    round: PublicKey,
+    placed: { bet: PublicKey; betMint: PublicKey },
+    userBetNft: PublicKey | null = getAssociatedTokenAddressSync(placed.betMint, user.publicKey)
+  ) {
+    await program.methods
+      .claimCompensation()
+      .accounts({
+        user: user.publicKey,
+        tournament,
+        state: statePda,
+        round,
+        bet: placed.bet,
+        userBetNft,
+        compensationVault: pda(Buffer.from("compensation"), round.toBuffer()),
+        userMatchpToken: getAssociatedTokenAddressSync(matchpMint.publicKey, user.publicKey),
+        matchpMint: matchpMint.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([user])
+      .rpc();
+  }
+
+
+
   // 已经通过的测试用例，暂时注释
   /*
   it("初始化合约", async () => {
@@ -423,217 +889,104 @@ describe("tournament-betting-system", () => {
   // 创建简化的测试用例，专注于质押功能
   it("简化测试质押下注", async () => {
     console.log("开始简化质押测试...");
+    await ensureState();
 
-    // 创建vote代币的铸币账户，确保我们有完整控制权
-    const mintKeypair = anchor.web3.Keypair.generate();
-    const payer = provider.wallet;
-    
-    // 创建质押测试所需的账户
-    const authority = anchor.web3.Keypair.generate();
-    console.log("创建测试账户...");
-    
-    // 为authority提供SOL
-    const airdropAuthority = await provider.connection.requestAirdrop(
-      authority.publicKey,
-      2000000000
-    );
-    await provider.connection.confirmTransaction(airdropAuthority);
-    
-    // 创建代币铸币机
-    console.log("创建代币铸币机...");
-    
-    // 创建一个独立的voteMintAuthority账户
-    const voteMintAuthority = anchor.web3.Keypair.generate();
-    
-    // 为voteMintAuthority提供SOL
-    const airdropVoteAuth = await provider.connection.requestAirdrop(
-      voteMintAuthority.publicKey,
-      1000000000
-    );
-    await provider.connection.confirmTransaction(airdropVoteAuth);
-    
-    // 创建vote代币，确保铸币权限为单独的voteMintAuthority账户
-    const voteMint = await createMint(
-      provider.connection, 
-      payer.payer,   // 支付SOL交易费用的账户
-      voteMintAuthority.publicKey, // 铸币权限
-      null,          // 无冻结权限
-      9              // 小数点位置
-    );
-    
-    // 检查铸币权限是否正确设置
-    const mintInfo = await getMint(provider.connection, voteMint);
-    console.log("Vote代币铸币权限:", mintInfo.mintAuthority?.toBase58());
-    console.log("voteMintAuthority公钥:", voteMintAuthority.publicKey.toBase58());
-    
-    if (!mintInfo.mintAuthority || !mintInfo.mintAuthority.equals(voteMintAuthority.publicKey)) {
-      throw new Error("铸币权限设置错误");
-    }
-    
-    // 创建matchp代币
-    const matchpMint = await createMint(
-      provider.connection,
-      payer.payer,
-      authority.publicKey,
-      null,
-      9
-    );
-    
-    console.log("创建代币账户...");
-    // 创建authority的matchp代币账户
-    const authorityMatchpAccount = await getOrCreateAssociatedTokenAccount(
-      provider.connection,
-      payer.payer,
-      matchpMint,
-      authority.publicKey
-    );
-    
-    // 创建authority的vote代币账户
-    const authorityVoteAccount = await getOrCreateAssociatedTokenAccount(
-      provider.connection,
-      payer.payer,
-      voteMint,
-      authority.publicKey
-    );
-    
-    // 给authority铸造一些matchp代币用于质押
+    // 创建质押测试所需的账户，每个赛事方只能创建一个赛事
+    const stakeAuthority = await fundedKeypair();
+
+    // vote代币由合约按赛事创建，铸币权限为赛事PDA
+    const tournament = pda(Buffer.from("tournament"), stakeAuthority.publicKey.toBuffer());
+    const voteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+    const tournamentMatchpToken = pda(Buffer.from("tournament_matchp"), tournament.toBuffer());
+    const tournamentVoteToken = pda(Buffer.from("tournament_vote"), tournament.toBuffer());
+    const stakePosition = pda(Buffer.from("stake_position"), tournament.toBuffer(), stakeAuthority.publicKey.toBuffer());
+
+    // 给赛事方铸造一些matchp代币用于质押
     console.log("铸造matchp代币...");
-    await mintTo(
-      provider.connection,
-      payer.payer,
-      matchpMint,
-      authorityMatchpAccount.address,
-      authority,
-      10000000
-    );
-    
-    // 预计算tournament PDA地址
-    const [tournament] = await anchor.web3.PublicKey.findProgramAddress(
-      [Buffer.from("tournament"), authority.publicKey.toBuffer()],
-      program.programId
-    );
-    
-    // 预计算tournament token PDA地址
-    const [tournamentMatchpToken] = await anchor.web3.PublicKey.findProgramAddress(
-      [Buffer.from("tournament_matchp"), tournament.toBuffer()],
-      program.programId
-    );
-    
-    const [tournamentVoteToken] = await anchor.web3.PublicKey.findProgramAddress(
-      [Buffer.from("tournament_vote"), tournament.toBuffer()],
-      program.programId
-    );
-    
-    // 关键修改：将vote代币的铸币权限转移给tournament PDA
-    console.log("转移vote代币铸币权限给tournament PDA...");
-    await setAuthority(
-      provider.connection,
-      payer.payer,
-      voteMint,
-      voteMintAuthority,
-      0, // MintTokens authority type
-      tournament,
-      []
-    );
-    
-    // 验证铸币权限已成功转移
-    const updatedMintInfo = await getMint(provider.connection, voteMint);
-    console.log("更新后的铸币权限:", updatedMintInfo.mintAuthority?.toBase58());
-    console.log("Tournament PDA:", tournament.toBase58());
-    
-    if (!updatedMintInfo.mintAuthority || !updatedMintInfo.mintAuthority.equals(tournament)) {
-      throw new Error("铸币权限转移失败");
-    }
-    
+    const authorityMatchpAccount = await tokenAccount(matchpMint.publicKey, stakeAuthority.publicKey, 10000000);
+    const authorityVoteAccount = getAssociatedTokenAddressSync(voteMint, stakeAuthority.publicKey);
+
     // 设置质押金额为0，先不要质押
     const createStakeAmount = new anchor.BN(0);
-    
+
     console.log("第一步：创建赛事（不质押）...");
-    try {
-      // 调用合约创建赛事，stake_amount = 0，不会尝试铸币
-      const tx = await program.methods
-        .createTournament("质押测试赛事", createStakeAmount)
-        .accounts({
-          authority: authority.publicKey,
-          tournament: tournament,
-          authorityMatchpToken: authorityMatchpAccount.address,
-          authorityVoteToken: authorityVoteAccount.address,
-          tournamentMatchpToken: tournamentMatchpToken,
-          tournamentVoteToken: tournamentVoteToken,
-          matchpMint: matchpMint,
-          voteMint: voteMint,
-          systemProgram: anchor.web3.SystemProgram.programId,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-        } as any)
-        .signers([authority])
-        .rpc();
-      
-      console.log("赛事创建成功，交易ID:", tx);
-      
-      // 验证赛事创建成功
-      const tournamentAccount = await program.account.tournament.fetch(tournament);
-      console.log("赛事账户:", tournamentAccount);
-      expect(tournamentAccount.name).to.equal("质押测试赛事");
-      expect(tournamentAccount.stakeAmount.eq(createStakeAmount)).to.be.true;
-      expect(tournamentAccount.isActive).to.be.true;
-      expect(tournamentAccount.isStaked).to.be.false;
-      
-      // 实现质押功能
-      console.log("第二步：执行质押操作");
-      const stakeAmount = new anchor.BN(1000);
-      
-      try {
-        // 调用质押函数
-        const stakeTx = await program.methods
-          .stakeTournament(stakeAmount)
-          .accounts({
-            authority: authority.publicKey,
-            tournament: tournament,
-            authorityMatchpToken: authorityMatchpAccount.address,
-            authorityVoteToken: authorityVoteAccount.address,
-            tournamentMatchpToken: tournamentMatchpToken,
-            matchpMint: matchpMint,
-            voteMint: voteMint,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          } as any)
-          .signers([authority])
-          .rpc();
-        
-        console.log("质押成功，交易ID:", stakeTx);
-        
-        // 验证质押成功
-        const tournamentAccountAfterStake = await program.account.tournament.fetch(tournament);
-        console.log("质押后的赛事账户:", tournamentAccountAfterStake);
-        expect(tournamentAccountAfterStake.stakeAmount.eq(stakeAmount)).to.be.true;
-        expect(tournamentAccountAfterStake.isStaked).to.be.true;
-        
-        // 验证vote代币是否已铸造给authority
-        const voteTokenInfo = await provider.connection.getTokenAccountBalance(authorityVoteAccount.address);
-        console.log("铸币权限持有者的vote代币余额:", voteTokenInfo.value.amount);
-        expect(Number(voteTokenInfo.value.amount)).to.be.at.least(stakeAmount.toNumber());
-        
-        console.log("质押测试完成，一切正常！");
-      } catch (stakeError) {
-        console.error("质押操作失败，错误详情:");
-        console.error(stakeError);
-        if (stakeError.logs) {
-          console.error(stakeError.logs.join('\n'));
-        } else {
-          console.error(stakeError.message);
-        }
-        throw stakeError;
-      }
-    } catch (error) {
-      console.error("测试失败，错误详情:");
-      console.error(error);
-      if (error.logs) {
-        console.error(error.logs.join('\n'));
-      } else {
-        console.error(error.message);
-      }
-      throw error;
-    }
+    const tx = await program.methods
+      .createTournament("质押测试赛事", createStakeAmount)
+      .accounts({
+        authority: stakeAuthority.publicKey,
+        tournament: tournament,
+        state: statePda,
+        operatorRole: null,
+        currencyMint: wanziMint.publicKey,
+        currencyAllowlist: null,
+        authorityMatchpToken: authorityMatchpAccount,
+        authorityVoteToken: authorityVoteAccount,
+        tournamentMatchpToken: tournamentMatchpToken,
+        tournamentVoteToken: tournamentVoteToken,
+        stakePosition: stakePosition,
+        matchpMint: matchpMint.publicKey,
+        voteMint: voteMint,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      } as any)
+      .signers([stakeAuthority])
+      .rpc();
+    console.log("赛事创建成功，交易ID:", tx);
+
+    // 验证赛事创建成功
+    const tournamentAccount = await program.account.tournament.fetch(tournament);
+    expect(tournamentAccount.name).to.equal("质押测试赛事");
+    expect(tournamentAccount.stakeAmount.eq(createStakeAmount)).to.be.true;
+    expect(tournamentAccount.isActive).to.be.true;
+    expect(tournamentAccount.isStaked).to.be.false;
+    expect(tournamentAccount.voteMint.equals(voteMint)).to.be.true;
+
+    // 检查铸币权限是否为赛事PDA
+    const mintInfo = await getMint(provider.connection, voteMint);
+    expect(mintInfo.mintAuthority?.equals(tournament)).to.be.true;
+
+    // 实现质押功能
+    console.log("第二步：执行质押操作");
+    const stakeAmount = new anchor.BN(1000);
+    const stakeTx = await program.methods
+      .stakeTournament(stakeAmount)
+      .accounts({
+        authority: stakeAuthority.publicKey,
+        tournament: tournament,
+        state: statePda,
+        authorityMatchpToken: authorityMatchpAccount,
+        authorityVoteToken: authorityVoteAccount,
+        tournamentMatchpToken: tournamentMatchpToken,
+        matchpMint: matchpMint.publicKey,
+        voteMint: voteMint,
+        stakePosition: stakePosition,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([stakeAuthority])
+      .rpc();
+    console.log("质押成功，交易ID:", stakeTx);
+
+    // 验证质押成功
+    const tournamentAccountAfterStake = await program.account.tournament.fetch(tournament);
+    expect(tournamentAccountAfterStake.stakeAmount.eq(stakeAmount)).to.be.true;
+    expect(tournamentAccountAfterStake.isStaked).to.be.true;
+
+    // 验证vote代币是否已铸造给赛事方
+    const voteTokenInfo = await provider.connection.getTokenAccountBalance(authorityVoteAccount);
+    expect(Number(voteTokenInfo.value.amount)).to.be.at.least(stakeAmount.toNumber());
+
+    // 质押后用vote代币下注
+    const round = await createRound(stakeAuthority, tournament, 1);
+    const team = await createTeam(stakeAuthority, tournament, round, "A");
+    const { bet } = await placeBet(stakeAuthority, tournament, round, team, 400);
+    const betAccount = await program.account.bet.fetch(bet);
+    expect(betAccount.amount.toNumber()).to.equal(400);
+    expect(await balance(authorityVoteAccount)).to.equal(600);
+    expect(await balance(tournamentVoteToken)).to.equal(400);
+    console.log("质押测试完成，一切正常！");
   });
 
   // 新增：多用户下注与结算测试
@@ -661,20 +1014,20 @@ describe("tournament-betting-system", () => {
       throw e;
     }
 
-    // 3. 创建vote代币账户
-    let user1_vote_token: any, user2_vote_token: any;
+    // 3. 创建全局vote代币账户
+    let user1_global_vote_token: any, user2_global_vote_token: any;
     try {
       console.log("[多用户测试] 准备为user1创建vote代币账户...");
-      user1_vote_token = await getOrCreateAssociatedTokenAccount(
+      user1_global_vote_token = await getOrCreateAssociatedTokenAccount(
         provider.connection, authority, voteMint.publicKey, user1.publicKey
       );
-      console.log("[多用户测试] user1 vote代币账户创建成功:", user1_vote_token.address.toBase58());
+      console.log("[多用户测试] user1 vote代币账户创建成功:", user1_global_vote_token.address.toBase58());
 
       console.log("[多用户测试] 准备为user2创建vote代币账户...");
-      user2_vote_token = await getOrCreateAssociatedTokenAccount(
+      user2_global_vote_token = await getOrCreateAssociatedTokenAccount(
         provider.connection, authority, voteMint.publicKey, user2.publicKey
       );
-      console.log("[多用户测试] user2 vote代币账户创建成功:", user2_vote_token.address.toBase58());
+      console.log("[多用户测试] user2 vote代币账户创建成功:", user2_global_vote_token.address.toBase58());
     } catch (e) {
       console.error("[多用户测试] vote代币账户创建失败:", e);
       throw e;
@@ -684,13 +1037,13 @@ describe("tournament-betting-system", () => {
     try {
       console.log("[多用户测试] 准备向user1铸造vote代币...");
       await mintTo(
-        provider.connection, authority, voteMint.publicKey, user1_vote_token.address, authority, 5000
+        provider.connection, authority, voteMint.publicKey, user1_global_vote_token.address, authority, 5000
       );
       console.log("[多用户测试] user1 vote代币铸造成功。");
 
       console.log("[多用户测试] 准备向user2铸造vote代币...");
       await mintTo(
-        provider.connection, authority, voteMint.publicKey, user2_vote_token.address, authority, 5000
+        provider.connection, authority, voteMint.publicKey, user2_global_vote_token.address, authority, 5000
       );
       console.log("[多用户测试] user2 vote代币铸造成功。");
     } catch (e) {
@@ -714,23 +1067,52 @@ describe("tournament-betting-system", () => {
       [Buffer.from("tournament_vote"), tournament_pda.toBuffer()],
       program.programId
     );
-    // 新增：计算 tournament_wanzi_token 的 PDA
-    const [tournament_wanzi_token_pda] = await anchor.web3.PublicKey.findProgramAddress(
-      [Buffer.from("tournament_wanzi"), tournament_pda.toBuffer()],
+    // 赛事下注代币(wanzi)的托管账户 PDA
+    const [currency_vault_pda] = await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("currency_vault"), tournament_pda.toBuffer()],
       program.programId
     );
-
-    // 获取或创建 authority 的代币账户
-    const authority_matchp_token_address = await getOrCreateAssociatedTokenAccount(
-      provider.connection,
-      authority, // payer
-      matchpMint.publicKey,
-      authority.publicKey
-    );
-    const authority_vote_token_address = await getOrCreateAssociatedTokenAccount(
+    // 合约状态账户，createTournament 用它校验下注代币
+    const [state_pda] = await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("state")],
+      program.programId
+    );
+    if (!(await provider.connection.getAccountInfo(state_pda))) {
+      await program.methods
+        .initialize(new PublicKey("TokenFaucetProgram1111111111111111111111111"))
+        .accounts({
+          authority: authority.publicKey,
+          state: state_pda,
+          wanziMint: wanziMint.publicKey,
+          matchpMint: matchpMint.publicKey,
+          voteMint: voteMint.publicKey,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        } as any)
+        .signers([authority])
+        .rpc();
+    }
+    // 赛事专属的vote代币铸造器，由createTournament创建
+    const [tournament_vote_mint_pda] = await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("vote_mint"), tournament_pda.toBuffer()],
+      program.programId
+    );
+
+    // 获取或创建 authority 的代币账户
+    const authority_matchp_token_address = await getOrCreateAssociatedTokenAccount(
       provider.connection,
       authority, // payer
-      voteMint.publicKey,
+      matchpMint.publicKey,
+      authority.publicKey
+    );
+    // 赛事方的质押份额记录
+    const [stake_position_pda] = await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("stake_position"), tournament_pda.toBuffer(), authority.publicKey.toBuffer()],
+      program.programId
+    );
+    // authority 的赛事vote代币账户由 createTournament 创建
+    const authority_vote_token_address = await getAssociatedTokenAddress(
+      tournament_vote_mint_pda,
       authority.publicKey
     );
 
@@ -765,14 +1147,21 @@ describe("tournament-betting-system", () => {
         .accounts({
           authority: authority.publicKey,
           tournament: tournament_pda,
+          state: state_pda,
+          operatorRole: null,
+          currencyMint: wanziMint.publicKey,
+          currencyAllowlist: null,
           authorityMatchpToken: authority_matchp_token_address.address,
-          authorityVoteToken: authority_vote_token_address.address,
+          authorityVoteToken: authority_vote_token_address,
           tournamentMatchpToken: tournament_matchp_token_pda,
           tournamentVoteToken: tournament_vote_token_pda,
+          stakePosition: stake_position_pda,
           matchpMint: matchpMint.publicKey,
-          voteMint: voteMint.publicKey,
+          voteMint: tournament_vote_mint_pda,
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
+          currencyTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         })
         .signers([authority])
@@ -796,6 +1185,14 @@ describe("tournament-betting-system", () => {
       throw e; // 重新抛出错误，以便测试失败
     }
 
+    // 赛事专属vote代币账户，下注和结算使用该赛事的vote代币
+    const user1_vote_token = await getOrCreateAssociatedTokenAccount(
+      provider.connection, authority, tournament_vote_mint_pda, user1.publicKey
+    );
+    const user2_vote_token = await getOrCreateAssociatedTokenAccount(
+      provider.connection, authority, tournament_vote_mint_pda, user2.publicKey
+    );
+
     // 6. 创建轮次和队伍
     const round_number = 1;
     const round_name = "第1轮";
@@ -930,17 +1327,21 @@ describe("tournament-betting-system", () => {
       .accounts({
         user: user1.publicKey,
         tournament: tournament_pda,
+        state: state_pda,
         round: round_pda,
         team: team1_pda,
         bet: bet1_keypair.publicKey,
-        userWanziToken: user1_wanzi_token.address,
+        userCurrencyToken: user1_wanzi_token.address,
         userVoteToken: user1_vote_token.address,
-        tournamentWanziToken: tournament_wanzi_token_pda,
+        currencyVault: currency_vault_pda,
+        solVault: null,
         tournamentVoteToken: tournament_vote_token_pda,
-        wanziMint: wanziMint.publicKey,
-        voteMint: voteMint.publicKey,
+        currencyMint: wanziMint.publicKey,
+        voteMint: tournament_vote_mint_pda,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
+        nftTokenProgram: TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       } as any)
       .signers([user1, bet1_keypair])
@@ -950,17 +1351,21 @@ describe("tournament-betting-system", () => {
       .accounts({
         user: user2.publicKey,
         tournament: tournament_pda,
+        state: state_pda,
         round: round_pda,
         team: team2_pda,
         bet: bet2_keypair.publicKey,
-        userWanziToken: user2_wanzi_token.address,
+        userCurrencyToken: user2_wanzi_token.address,
         userVoteToken: user2_vote_token.address,
-        tournamentWanziToken: tournament_wanzi_token_pda,
+        currencyVault: currency_vault_pda,
+        solVault: null,
         tournamentVoteToken: tournament_vote_token_pda,
-        wanziMint: wanziMint.publicKey,
-        voteMint: voteMint.publicKey,
+        currencyMint: wanziMint.publicKey,
+        voteMint: tournament_vote_mint_pda,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
+        nftTokenProgram: TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       } as any)
       .signers([user2, bet2_keypair])
@@ -973,6 +1378,7 @@ describe("tournament-betting-system", () => {
         tournament: tournament_pda,
         round: round_pda,
         winnerTeam: team1_pda,
+        oracleResult: null,
       } as any)
       .signers([authority])
       .rpc();
@@ -980,41 +1386,51 @@ describe("tournament-betting-system", () => {
     await program.methods
       .settleBet()
       .accounts({
-        authority: authority.publicKey,
         tournament: tournament_pda,
         round: round_pda,
         winnerTeam: team1_pda,
         bet: bet1_keypair.publicKey,
         user: user1.publicKey,
-        userWanziToken: user1_wanzi_token.address,
+        userCurrencyToken: user1_wanzi_token.address,
         userVoteToken: user1_vote_token.address,
-        tournamentWanziToken: tournament_wanzi_token_pda,
+        currencyVault: currency_vault_pda,
+        solVault: null,
         tournamentVoteToken: tournament_vote_token_pda,
+        currencyMint: wanziMint.publicKey,
+        voteMint: tournament_vote_mint_pda,
+        rewardsVault: null,
         tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
+        nftTokenProgram: TOKEN_PROGRAM_ID,
       } as any)
-      .signers([authority])
+      .signers([user1])
       .rpc();
     await program.methods
       .settleBet()
       .accounts({
-        authority: authority.publicKey,
         tournament: tournament_pda,
         round: round_pda,
         winnerTeam: team1_pda,
         bet: bet2_keypair.publicKey,
         user: user2.publicKey,
-        userWanziToken: user2_wanzi_token.address,
+        userCurrencyToken: user2_wanzi_token.address,
         userVoteToken: user2_vote_token.address,
-        tournamentWanziToken: tournament_wanzi_token_pda,
+        currencyVault: currency_vault_pda,
+        solVault: null,
         tournamentVoteToken: tournament_vote_token_pda,
+        currencyMint: wanziMint.publicKey,
+        voteMint: tournament_vote_mint_pda,
+        rewardsVault: null,
         tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
+        nftTokenProgram: TOKEN_PROGRAM_ID,
       } as any)
-      .signers([authority])
+      .signers([user2])
       .rpc();
     // 10. 验证余额
-    const user1VoteBalance = await provider.connection.getTokenAccountBalance(user1_vote_token.address);
+    const user1VoteBalance = await provider.connection.getTokenAccountBalance(user1_global_vote_token.address);
     const user1WanziBalance = await provider.connection.getTokenAccountBalance(user1_wanzi_token.address);
-    const user2VoteBalance = await provider.connection.getTokenAccountBalance(user2_vote_token.address);
+    const user2VoteBalance = await provider.connection.getTokenAccountBalance(user2_global_vote_token.address);
     const user2WanziBalance = await provider.connection.getTokenAccountBalance(user2_wanzi_token.address);
 
     console.log(`[多用户测试] User1 Vote Balance: ${user1VoteBalance.value.amount}`);
@@ -1026,6 +1442,2329 @@ describe("tournament-betting-system", () => {
     expect(Number(user1WanziBalance.value.amount)).to.equal(7000, "User1 Wanzi Balance check failed");
     expect(Number(user2VoteBalance.value.amount)).to.equal(5000, "User2 Vote Balance check failed");
     expect(Number(user2WanziBalance.value.amount)).to.equal(3000, "User2 Wanzi Balance check failed");
+    // 未质押赛事下注使用wanzi，赛事vote代币不发生变动
+    const user1TournamentVoteBalance = await provider.connection.getTokenAccountBalance(user1_vote_token.address);
+    const user2TournamentVoteBalance = await provider.connection.getTokenAccountBalance(user2_vote_token.address);
+    expect(Number(user1TournamentVoteBalance.value.amount)).to.equal(0, "User1 Tournament Vote Balance check failed");
+    expect(Number(user2TournamentVoteBalance.value.amount)).to.equal(0, "User2 Tournament Vote Balance check failed");
+  });
+
+  describe("下注转让", () => {
+    let creator: anchor.web3.Keypair;
+    let holder: anchor.web3.Keypair;
+    let receiver: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let round: PublicKey;
+    let team: PublicKey;
+    let placed: { bet: PublicKey; betMint: PublicKey };
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      tournament = await createTournament(creator, "转让测试赛事");
+      round = await createRound(creator, tournament, 1);
+      team = await createTeam(creator, tournament, round, "A");
+
+      holder = await fundedKeypair();
+      receiver = await fundedKeypair();
+      await tokenAccount(wanziMint.publicKey, holder.publicKey, 5000);
+      await tokenAccount(wanziMint.publicKey, receiver.publicKey);
+      placed = await placeBet(holder, tournament, round, team, 1000);
+    });
+
+    const transferBet = (signer: anchor.web3.Keypair, from: PublicKey, to: PublicKey) =>
+      program.methods
+        .transferBet()
+        .accounts({
+          user: signer.publicKey,
+          bet: placed.bet,
+          userBetNft: getAssociatedTokenAddressSync(placed.betMint, from),
+          newOwner: to,
+          newOwnerBetNft: getAssociatedTokenAddressSync(placed.betMint, to),
+          betMint: placed.betMint,
+          systemProgram: SystemProgram.programId,
+          nftTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([signer])
+        .rpc();
+
+    it("不持有下注NFT时拒绝转让", async () => {
+      await expectError(transferBet(receiver, holder.publicKey, receiver.publicKey), "NotBetOwner");
+      expect((await program.account.bet.fetch(placed.bet)).user.equals(holder.publicKey)).to.be.true;
+    });
+
+    it("转让下注后由新持有者结算", async () => {
+      await transferBet(holder, holder.publicKey, receiver.publicKey);
+      const betAccount = await program.account.bet.fetch(placed.bet);
+      expect(betAccount.user.equals(receiver.publicKey)).to.be.true;
+      expect(await balance(getAssociatedTokenAddressSync(placed.betMint, receiver.publicKey))).to.equal(1);
+      expect(await balance(getAssociatedTokenAddressSync(placed.betMint, holder.publicKey))).to.equal(0);
+
+      await completeRound(creator, tournament, round, team);
+
+      const tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+      const receiverWanzi = getAssociatedTokenAddressSync(wanziMint.publicKey, receiver.publicKey);
+      await program.methods
+        .settleBet()
+        .accounts({
+          tournament,
+          round,
+          winnerTeam: team,
+          bet: placed.bet,
+          user: receiver.publicKey,
+          userBetNft: getAssociatedTokenAddressSync(placed.betMint, receiver.publicKey),
+          betMint: placed.betMint,
+          userCurrencyToken: receiverWanzi,
+          userVoteToken: await tokenAccount(tournamentVoteMint, receiver.publicKey),
+          currencyVault: pda(Buffer.from("currency_vault"), tournament.toBuffer()),
+          solVault: null,
+          tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+          rewardsVault: null,
+          currencyMint: wanziMint.publicKey,
+          voteMint: tournamentVoteMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          currencyTokenProgram: TOKEN_PROGRAM_ID,
+          nftTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([receiver])
+        .rpc();
+      // 唯一的下注获胜，新持有者收回全部彩池
+      expect(await balance(receiverWanzi)).to.equal(1000);
+      expect((await program.account.bet.fetch(placed.bet)).isSettled).to.be.true;
+    });
+  });
+
+  describe("下注挂单交易", () => {
+    let creator: anchor.web3.Keypair;
+    let seller: anchor.web3.Keypair;
+    let buyer: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let round: PublicKey;
+    let team: PublicKey;
+    let placed: { bet: PublicKey; betMint: PublicKey };
+    let listing: PublicKey;
+    let escrow: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      tournament = await createTournament(creator, "挂单测试赛事");
+      round = await createRound(creator, tournament, 1);
+      team = await createTeam(creator, tournament, round, "A");
+
+      seller = await fundedKeypair();
+      buyer = await fundedKeypair();
+      await tokenAccount(wanziMint.publicKey, seller.publicKey, 5000);
+      await tokenAccount(wanziMint.publicKey, buyer.publicKey, 5000);
+      placed = await placeBet(seller, tournament, round, team, 1000);
+      listing = pda(Buffer.from("listing"), placed.bet.toBuffer());
+      escrow = pda(Buffer.from("listing_escrow"), placed.bet.toBuffer());
+    });
+
+    const listBet = (signer: anchor.web3.Keypair, owner: PublicKey, price: number) =>
+      program.methods
+        .listBet(new anchor.BN(price))
+        .accounts({
+          seller: signer.publicKey,
+          bet: placed.bet,
+          round,
+          sellerBetNft: getAssociatedTokenAddressSync(placed.betMint, owner),
+          betMint: placed.betMint,
+          listing,
+          escrowBetNft: escrow,
+          systemProgram: SystemProgram.programId,
+          nftTokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        } as any)
+        .signers([signer])
+        .rpc();
+
+    const buyBet = (signer: anchor.web3.Keypair, listedBy: PublicKey) =>
+      program.methods
+        .buyBet()
+        .accounts({
+          buyer: signer.publicKey,
+          state: statePda,
+          bet: placed.bet,
+          round,
+          listing,
+          seller: listedBy,
+          buyerWanziToken: getAssociatedTokenAddressSync(wanziMint.publicKey, signer.publicKey),
+          sellerWanziToken: getAssociatedTokenAddressSync(wanziMint.publicKey, listedBy),
+          escrowBetNft: escrow,
+          buyerBetNft: getAssociatedTokenAddressSync(placed.betMint, signer.publicKey),
+          betMint: placed.betMint,
+          systemProgram: SystemProgram.programId,
+          wanziMint: wanziMint.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          nftTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([signer])
+        .rpc();
+
+    it("只有下注NFT持有者可以挂单，卖家不能购买自己的挂单", async () => {
+      await expectError(listBet(buyer, seller.publicKey, 500), "NotBetOwner");
+
+      await listBet(seller, seller.publicKey, 500);
+      expect(await balance(escrow)).to.equal(1);
+      await expectError(buyBet(seller, seller.publicKey), "InvalidBetRecipient");
+    });
+
+    it("买家支付标价后获得下注NFT", async () => {
+      const sellerWanzi = getAssociatedTokenAddressSync(wanziMint.publicKey, seller.publicKey);
+      const buyerWanzi = getAssociatedTokenAddressSync(wanziMint.publicKey, buyer.publicKey);
+      const sellerBalanceBefore = await balance(sellerWanzi);
+      const buyerBalanceBefore = await balance(buyerWanzi);
+
+      await buyBet(buyer, seller.publicKey);
+      expect(await balance(sellerWanzi)).to.equal(sellerBalanceBefore + 500);
+      expect(await balance(buyerWanzi)).to.equal(buyerBalanceBefore - 500);
+      expect(await balance(getAssociatedTokenAddressSync(placed.betMint, buyer.publicKey))).to.equal(1);
+      expect((await program.account.bet.fetch(placed.bet)).user.equals(buyer.publicKey)).to.be.true;
+      // 成交后挂单和托管账户都已关闭
+      expect(await provider.connection.getAccountInfo(listing)).to.equal(null);
+      expect(await provider.connection.getAccountInfo(escrow)).to.equal(null);
+    });
+
+    it("撤单退回下注NFT，轮次出结果后不能再挂单", async () => {
+      await listBet(buyer, buyer.publicKey, 800);
+      await program.methods
+        .cancelListing()
+        .accounts({
+          seller: buyer.publicKey,
+          bet: placed.bet,
+          listing,
+          escrowBetNft: escrow,
+          sellerBetNft: getAssociatedTokenAddressSync(placed.betMint, buyer.publicKey),
+          betMint: placed.betMint,
+          nftTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([buyer])
+        .rpc();
+      expect(await balance(getAssociatedTokenAddressSync(placed.betMint, buyer.publicKey))).to.equal(1);
+      expect(await provider.connection.getAccountInfo(listing)).to.equal(null);
+
+      await completeRound(creator, tournament, round, team);
+      await expectError(listBet(buyer, buyer.publicKey, 800), "RoundClosedForTrading");
+    });
+  });
+
+  describe("大小盘与总分市场", () => {
+    let creator: anchor.web3.Keypair;
+    let over: anchor.web3.Keypair;
+    let under: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let round: PublicKey;
+    let team: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      tournament = await createTournament(creator, "大小盘测试赛事");
+      round = await createRound(creator, tournament, 1);
+      team = await createTeam(creator, tournament, round, "A");
+
+      over = await fundedKeypair();
+      under = await fundedKeypair();
+      await tokenAccount(wanziMint.publicKey, over.publicKey, 10_000);
+      await tokenAccount(wanziMint.publicKey, under.publicKey, 10_000);
+    });
+
+    // 大小盘盘口以半分为单位：91表示45.5分，90表示45分(恰好45分时走盘)
+    const createMarket = async (marketRound: PublicKey, index: number, name: string, line: number) => {
+      const market = pda(Buffer.from("market"), marketRound.toBuffer(), Buffer.from([index]));
+      await program.methods
+        .createMarket(index, name, { overUnder: {} } as any, new anchor.BN(line), [])
+        .accounts({
+          authority: creator.publicKey,
+          tournament,
+          round: marketRound,
+          market,
+          favoriteTeam: null,
+          underdogTeam: null,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([creator])
+        .rpc();
+      return market;
+    };
+
+    const reportScore = (marketRound: PublicKey, market: PublicKey, value: number) =>
+      program.methods
+        .reportScore(new anchor.BN(value))
+        .accounts({ authority: creator.publicKey, tournament, market, round: marketRound } as any)
+        .signers([creator])
+        .rpc();
+
+    it("按总分结算，恰好等于盘口时走盘退款", async () => {
+      const overWanzi = getAssociatedTokenAddressSync(wanziMint.publicKey, over.publicKey);
+      const underWanzi = getAssociatedTokenAddressSync(wanziMint.publicKey, under.publicKey);
+      const totalMarket = await createMarket(round, 0, "总分大小", 91);
+      const pushMarket = await createMarket(round, 1, "总分走盘", 90);
+
+      const overTotal = await placeMarketBet(over, tournament, round, totalMarket, 1000, 0);
+      const underTotal = await placeMarketBet(under, tournament, round, totalMarket, 1000, 1);
+      const overPush = await placeMarketBet(over, tournament, round, pushMarket, 500, 0);
+      const underPush = await placeMarketBet(under, tournament, round, pushMarket, 700, 1);
+      expect(await balance(overWanzi)).to.equal(8500);
+      expect(await balance(underWanzi)).to.equal(8300);
+
+      await reportScore(round, totalMarket, 50);
+      await reportScore(round, pushMarket, 45);
+      expect((await program.account.market.fetch(pushMarket)).isPush).to.be.true;
+      await expectError(reportScore(round, totalMarket, 40), "MarketAlreadyResolved");
+
+      // 市场结果与轮次共用争议期，轮次完成前不能结算
+      await expectError(settleMarketBet(over, tournament, round, totalMarket, overTotal), "RoundNotCompleted");
+      await completeRound(creator, tournament, round, team);
+
+      // 获胜方瓜分整个彩池，失败方没有奖金
+      await settleMarketBet(over, tournament, round, totalMarket, overTotal);
+      await settleMarketBet(under, tournament, round, totalMarket, underTotal);
+      expect(await balance(overWanzi)).to.equal(10_500);
+      expect(await balance(underWanzi)).to.equal(8300);
+
+      // 走盘时双方都退还本金
+      await settleMarketBet(over, tournament, round, pushMarket, overPush);
+      await settleMarketBet(under, tournament, round, pushMarket, underPush);
+      expect(await balance(overWanzi)).to.equal(11_000);
+      expect(await balance(underWanzi)).to.equal(9000);
+      expect(await balance(pda(Buffer.from("currency_vault"), tournament.toBuffer()))).to.equal(0);
+    });
+
+    it("超过裁定期限后不能报告比分", async () => {
+      // 之后创建的轮次需在1秒内出结果
+      await program.methods
+        .configureSlashing(0, new anchor.BN(1))
+        .accounts({ authority: authority.publicKey, state: statePda, adminRole: null, tournament } as any)
+        .signers([authority])
+        .rpc();
+      const lateRound = await createRound(creator, tournament, 2);
+      const market = await createMarket(lateRound, 0, "总分大小", 91);
+
+      const { resolutionDeadline } = await program.account.tournamentRound.fetch(lateRound);
+      await waitForTimestamp(resolutionDeadline.toNumber());
+      await expectError(reportScore(lateRound, market, 50), "ResolutionDeadlinePassed");
+      expect((await program.account.market.fetch(market)).isResolved).to.be.false;
+    });
+  });
+
+  describe("让分盘", () => {
+    let creator: anchor.web3.Keypair;
+    let favoriteBettor: anchor.web3.Keypair;
+    let underdogBettor: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let round: PublicKey;
+    let favorite: PublicKey;
+    let underdog: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      tournament = await createTournament(creator, "让分盘测试赛事");
+      round = await createRound(creator, tournament, 1);
+      favorite = await createTeam(creator, tournament, round, "A");
+      underdog = await createTeam(creator, tournament, round, "B");
+
+      favoriteBettor = await fundedKeypair();
+      underdogBettor = await fundedKeypair();
+      await tokenAccount(wanziMint.publicKey, favoriteBettor.publicKey, 10_000);
+      await tokenAccount(wanziMint.publicKey, underdogBettor.publicKey, 10_000);
+    });
+
+    const createHandicap = (index: number, line: number) =>
+      program.methods
+        .createMarket(index, "A让3.5分", { handicap: {} } as any, new anchor.BN(line), [])
+        .accounts({
+          authority: creator.publicKey,
+          tournament,
+          round,
+          market: pda(Buffer.from("market"), round.toBuffer(), Buffer.from([index])),
+          favoriteTeam: favorite,
+          underdogTeam: underdog,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([creator])
+        .rpc();
+
+    const reportTeamScore = (team: PublicKey, score: number) =>
+      program.methods
+        .reportTeamScore(new anchor.BN(score))
+        .accounts({ authority: creator.publicKey, tournament, team, round } as any)
+        .signers([creator])
+        .rpc();
+
+    const resolveHandicap = (market: PublicKey) =>
+      program.methods
+        .resolveHandicapMarket()
+        .accounts({ authority: creator.publicKey, tournament, market, favoriteTeam: favorite, underdogTeam: underdog } as any)
+        .signers([creator])
+        .rpc();
+
+    it("让分必须为正，两队比分都报告后才能结算", async () => {
+      await expectError(createHandicap(0, 0), "InvalidMarketLine");
+
+      await createHandicap(0, 7);
+      const market = pda(Buffer.from("market"), round.toBuffer(), Buffer.from([0]));
+      await reportTeamScore(favorite, 80);
+      await expectError(resolveHandicap(market), "TeamNotScored");
+      await expectError(reportTeamScore(favorite, 90), "TeamAlreadyScored");
+    });
+
+    it("热门方赢超过让分时赢盘", async () => {
+      const market = pda(Buffer.from("market"), round.toBuffer(), Buffer.from([0]));
+      const favoriteBet = await placeMarketBet(favoriteBettor, tournament, round, market, 1000, 0);
+      const underdogBet = await placeMarketBet(underdogBettor, tournament, round, market, 1000, 1);
+
+      // 胜分差5分，超过3.5分的让分
+      await reportTeamScore(underdog, 75);
+      await resolveHandicap(market);
+      const resolved = await program.account.market.fetch(market);
+      expect(resolved.result.toNumber()).to.equal(5);
+      expect(resolved.winningOutcome).to.equal(0);
+      expect(resolved.isPush).to.be.false;
+
+      await completeRound(creator, tournament, round, favorite);
+      await settleMarketBet(favoriteBettor, tournament, round, market, favoriteBet);
+      await settleMarketBet(underdogBettor, tournament, round, market, underdogBet);
+      expect(await balance(getAssociatedTokenAddressSync(wanziMint.publicKey, favoriteBettor.publicKey))).to.equal(11_000);
+      expect(await balance(getAssociatedTokenAddressSync(wanziMint.publicKey, underdogBettor.publicKey))).to.equal(9000);
+    });
+  });
+
+  describe("裁定人多签", () => {
+    let creator: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let round: PublicKey;
+    let teamA: PublicKey;
+    let teamB: PublicKey;
+    let resolverSet: PublicKey;
+    let stakeVault: PublicKey;
+    const resolvers: anchor.web3.Keypair[] = [];
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      tournament = await createTournament(creator, "多签测试赛事");
+      round = await createRound(creator, tournament, 1);
+      teamA = await createTeam(creator, tournament, round, "A");
+      teamB = await createTeam(creator, tournament, round, "B");
+      resolverSet = pda(Buffer.from("resolver_set"), tournament.toBuffer());
+      stakeVault = pda(Buffer.from("resolver_stake_vault"), tournament.toBuffer());
+      for (let i = 0; i < 3; i++) {
+        const resolver = await fundedKeypair();
+        await tokenAccount(matchpMint.publicKey, resolver.publicKey, 100);
+        resolvers.push(resolver);
+      }
+    });
+
+    const resolverStake = (resolver: anchor.web3.Keypair) =>
+      pda(Buffer.from("resolver_stake"), tournament.toBuffer(), resolver.publicKey.toBuffer());
+
+    // 承诺绑定裁定人、轮次、队伍和salt
+    const commitment = (resolver: anchor.web3.Keypair, resultRound: PublicKey, team: PublicKey, salt: Buffer) =>
+      Array.from(
+        createHash("sha256")
+          .update(Buffer.concat([resolver.publicKey.toBuffer(), resultRound.toBuffer(), team.toBuffer(), salt]))
+          .digest()
+      );
+
+    const commit = (resolver: anchor.web3.Keypair, resultRound: PublicKey, team: PublicKey, salt: Buffer) =>
+      program.methods
+        .commitResult(commitment(resolver, resultRound, team, salt))
+        .accounts({
+          resolver: resolver.publicKey,
+          tournament,
+          resolverSet,
+          resolverStake: resolverStake(resolver),
+          round: resultRound,
+          resolution: pda(Buffer.from("round_resolution"), resultRound.toBuffer()),
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([resolver])
+        .rpc();
+
+    const reveal = (resolver: anchor.web3.Keypair, resultRound: PublicKey, team: PublicKey, salt: Buffer) =>
+      program.methods
+        .revealResult(Array.from(salt))
+        .accounts({
+          resolver: resolver.publicKey,
+          tournament,
+          resolverSet,
+          resolverStake: resolverStake(resolver),
+          round: resultRound,
+          team,
+          resolution: pda(Buffer.from("round_resolution"), resultRound.toBuffer()),
+        } as any)
+        .signers([resolver])
+        .rpc();
+
+    it("阈值无效或质押不足时拒绝，配置后赛事方不能直接完成轮次", async () => {
+      const configure = (threshold: number) =>
+        program.methods
+          .configureResolvers(resolvers.map((r) => r.publicKey), threshold, new anchor.BN(5), new anchor.BN(6), new anchor.BN(100))
+          .accounts({
+            authority: creator.publicKey,
+            tournament,
+            resolverSet,
+            systemProgram: SystemProgram.programId,
+          } as any)
+          .signers([creator])
+          .rpc();
+      await expectError(configure(4), "InvalidResolverThreshold");
+      // 3个裁定人中2人一致即完成轮次，每人质押100 matchp
+      await configure(2);
+
+      const stake = (resolver: anchor.web3.Keypair, amount: number) =>
+        program.methods
+          .stakeResolver(new anchor.BN(amount))
+          .accounts({
+            resolver: resolver.publicKey,
+            state: statePda,
+            tournament,
+            resolverSet,
+            resolverStake: resolverStake(resolver),
+            resolverMatchpToken: getAssociatedTokenAddressSync(matchpMint.publicKey, resolver.publicKey),
+            resolverStakeVault: stakeVault,
+            matchpMint: matchpMint.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+          } as any)
+          .signers([resolver])
+          .rpc();
+      await stake(resolvers[0], 50);
+      await expectError(commit(resolvers[0], round, teamA, randomBytes(32)), "InsufficientResolverStake");
+      await stake(resolvers[0], 50);
+      await stake(resolvers[1], 100);
+      await stake(resolvers[2], 100);
+      expect(await balance(stakeVault)).to.equal(300);
+
+      await expectError(completeRound(creator, tournament, round, teamA), "ResolverVoteRequired");
+    });
+
+    it("达到阈值的裁定人揭示一致结果后轮次完成", async () => {
+      const salts = resolvers.map(() => randomBytes(32));
+      for (const [i, resolver] of resolvers.entries()) {
+        await commit(resolver, round, teamA, salts[i]);
+      }
+      const { commitDeadline } = await program.account.roundResolution.fetch(
+        pda(Buffer.from("round_resolution"), round.toBuffer())
+      );
+      await waitForTimestamp(commitDeadline.toNumber());
+
+      await reveal(resolvers[0], round, teamA, salts[0]);
+      expect((await program.account.tournamentRound.fetch(round)).isCompleted).to.be.false;
+      await reveal(resolvers[1], round, teamA, salts[1]);
+      const completed = await program.account.tournamentRound.fetch(round);
+      expect(completed.isCompleted).to.be.true;
+      expect(completed.winnerTeam.equals(teamA)).to.be.true;
+      expect((await program.account.team.fetch(teamB)).isWinner).to.be.false;
+    });
+
+    it("揭示内容必须与承诺一致，揭示期结束后罚没未揭示的裁定人", async () => {
+      const nextRound = await createRound(creator, tournament, 2);
+      const nextTeamA = await createTeam(creator, tournament, nextRound, "A");
+      const nextTeamB = await createTeam(creator, tournament, nextRound, "B");
+      const resolution = pda(Buffer.from("round_resolution"), nextRound.toBuffer());
+      const treasury = pda(Buffer.from("treasury"), matchpMint.publicKey.toBuffer());
+      const salts = resolvers.map(() => randomBytes(32));
+      for (const [i, resolver] of resolvers.entries()) {
+        await commit(resolver, nextRound, nextTeamA, salts[i]);
+      }
+
+      // 提交期内不能揭示
+      await expectError(reveal(resolvers[0], nextRound, nextTeamA, salts[0]), "CommitPhaseOpen");
+      const deadlines = await program.account.roundResolution.fetch(resolution);
+      await waitForTimestamp(deadlines.commitDeadline.toNumber());
+
+      // 揭示的队伍或salt与承诺不一致
+      await expectError(reveal(resolvers[0], nextRound, nextTeamB, salts[0]), "InvalidReveal");
+      await expectError(reveal(resolvers[0], nextRound, nextTeamA, salts[1]), "InvalidReveal");
+      await reveal(resolvers[0], nextRound, nextTeamA, salts[0]);
+      await reveal(resolvers[1], nextRound, nextTeamA, salts[1]);
+
+      const penalize = (resolver: anchor.web3.Keypair) =>
+        program.methods
+          .penalizeUnrevealed()
+          .accounts({
+            caller: creator.publicKey,
+            state: statePda,
+            tournament,
+            resolverSet,
+            resolverStake: resolverStake(resolver),
+            round: nextRound,
+            resolution,
+            resolverStakeVault: stakeVault,
+            treasury,
+            matchpMint: matchpMint.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+          } as any)
+          .signers([creator])
+          .rpc();
+      await expectError(penalize(resolvers[2]), "RevealPhaseOpen");
+      await waitForTimestamp(deadlines.revealDeadline.toNumber());
+
+      const treasuryBefore = await balance(treasury);
+      await penalize(resolvers[2]);
+      expect((await program.account.resolverStake.fetch(resolverStake(resolvers[2]))).amount.toNumber()).to.equal(0);
+      expect(await balance(treasury)).to.equal(treasuryBefore + 100);
+      expect(await balance(stakeVault)).to.equal(200);
+
+      // 已揭示的裁定人不会被罚没
+      await expectError(penalize(resolvers[0]), "NotPenalizable");
+    });
+  });
+
+  describe("争议期与保证金", () => {
+    let creator: anchor.web3.Keypair;
+    let challenger: anchor.web3.Keypair;
+    let challengerWanzi: PublicKey;
+    let tournament: PublicKey;
+    const treasury = pda(Buffer.from("treasury"), wanziMint.publicKey.toBuffer());
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      tournament = await createTournament(creator, "争议测试赛事");
+      challenger = await fundedKeypair();
+      challengerWanzi = await tokenAccount(wanziMint.publicKey, challenger.publicKey, 10_000);
+    });
+
+    const configureDispute = (period: number, bond: number) =>
+      program.methods
+        .configureDispute(new anchor.BN(period), new anchor.BN(bond))
+        .accounts({ authority: creator.publicKey, tournament } as any)
+        .signers([creator])
+        .rpc();
+
+    const resolveDispute = (arbiter: anchor.web3.Keypair, round: PublicKey, original: PublicKey, proposed: PublicKey, overturn: boolean) =>
+      program.methods
+        .resolveDispute(overturn)
+        .accounts({
+          arbiter: arbiter.publicKey,
+          state: statePda,
+          resolverRole: null,
+          ruling: rulingAccounts(tournament, round, original, proposed, challenger.publicKey, wanziMint.publicKey, arbiter.publicKey),
+        } as any)
+        .signers([arbiter])
+        .rpc();
+
+    it("维持原结果时保证金没收到国库，由管理员提取", async () => {
+      await expectError(configureDispute(60, 0), "InvalidDisputeBond");
+      await configureDispute(60, 100);
+      const round = await createRound(creator, tournament, 1);
+      const teamA = await createTeam(creator, tournament, round, "A");
+      const teamB = await createTeam(creator, tournament, round, "B");
+      await completeRound(creator, tournament, round, teamA);
+
+      // 不能对当前获胜队伍发起争议
+      await expectError(challengeResult(challenger, tournament, round, teamA, wanziMint.publicKey), "InvalidTeam");
+      await challengeResult(challenger, tournament, round, teamB, wanziMint.publicKey);
+      expect((await program.account.tournamentRound.fetch(round)).isDisputed).to.be.true;
+      expect((await program.account.tournament.fetch(tournament)).openDisputes).to.equal(1);
+      expect(await balance(challengerWanzi)).to.equal(9900);
+
+      // 争议未裁决时不能修改争议配置，赛事方也不能裁决自己赛事的争议
+      await expectError(configureDispute(0, 0), "DisputeInProgress");
+      await expectError(resolveDispute(creator, round, teamA, teamB, false), "NotArbiter");
+
+      const treasuryBefore = await balance(treasury);
+      await resolveDispute(authority, round, teamA, teamB, false);
+      expect(await balance(treasury)).to.equal(treasuryBefore + 100);
+      expect(await balance(challengerWanzi)).to.equal(9900);
+      const upheld = await program.account.tournamentRound.fetch(round);
+      expect(upheld.isDisputed).to.be.false;
+      expect(upheld.winnerTeam.equals(teamA)).to.be.true;
+      expect((await program.account.tournament.fetch(tournament)).openDisputes).to.equal(0);
+
+      // 国库按代币区分，提取时铸造器必须与国库一致
+      const destination = await tokenAccount(wanziMint.publicKey, authority.publicKey);
+      const withdraw = (mint: PublicKey) =>
+        program.methods
+          .withdrawTreasury(new anchor.BN(100))
+          .accounts({
+            authority: authority.publicKey,
+            state: statePda,
+            treasuryRole: null,
+            treasury,
+            destination,
+            mint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          } as any)
+          .signers([authority])
+          .rpc();
+      await expectError(withdraw(matchpMint.publicKey), "InvalidTokenMint");
+      const destinationBefore = await balance(destination);
+      await withdraw(wanziMint.publicKey);
+      expect(await balance(destination)).to.equal(destinationBefore + 100);
+      expect(await balance(treasury)).to.equal(treasuryBefore);
+    });
+
+    it("推翻原结果时退还保证金并更正获胜队伍", async () => {
+      const round = await createRound(creator, tournament, 2);
+      const teamA = await createTeam(creator, tournament, round, "A");
+      const teamB = await createTeam(creator, tournament, round, "B");
+      await completeRound(creator, tournament, round, teamA);
+      const dispute = await challengeResult(challenger, tournament, round, teamB, wanziMint.publicKey);
+      expect(await balance(challengerWanzi)).to.equal(9800);
+
+      await resolveDispute(authority, round, teamA, teamB, true);
+      expect(await balance(challengerWanzi)).to.equal(9900);
+      const overturned = await program.account.tournamentRound.fetch(round);
+      expect(overturned.winnerTeam.equals(teamB)).to.be.true;
+      expect((await program.account.dispute.fetch(dispute)).isOverturned).to.be.true;
+      expect((await program.account.team.fetch(teamB)).isWinner).to.be.true;
+      expect((await program.account.team.fetch(teamA)).isWinner).to.be.false;
+    });
+  });
+
+  describe("vote代币治理争议", () => {
+    let creator: anchor.web3.Keypair;
+    let challenger: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let tournamentVoteMint: PublicKey;
+    let creatorVote: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      tournament = await createTournament(creator, "治理测试赛事", 10_000);
+      tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+      creatorVote = getAssociatedTokenAddressSync(tournamentVoteMint, creator.publicKey);
+      await program.methods
+        .configureDispute(new anchor.BN(60), new anchor.BN(100))
+        .accounts({ authority: creator.publicKey, tournament } as any)
+        .signers([creator])
+        .rpc();
+      challenger = await fundedKeypair();
+      await tokenAccount(matchpMint.publicKey, challenger.publicKey, 1000);
+    });
+
+    const configureGovernance = (period: number, quorum: number) =>
+      program.methods
+        .configureGovernance(new anchor.BN(period), new anchor.BN(quorum))
+        .accounts({ authority: creator.publicKey, tournament } as any)
+        .signers([creator])
+        .rpc();
+
+    it("投票期内不能裁决，投票推翻原结果后退还保证金", async () => {
+      await expectError(configureGovernance(0, 100), "InvalidVotePeriod");
+      await configureGovernance(4, 100);
+
+      const round = await createRound(creator, tournament, 1);
+      const teamA = await createTeam(creator, tournament, round, "A");
+      const teamB = await createTeam(creator, tournament, round, "B");
+      await completeRound(creator, tournament, round, teamA);
+
+      // 质押赛事的争议保证金使用matchp
+      const challengerMatchp = getAssociatedTokenAddressSync(matchpMint.publicKey, challenger.publicKey);
+      const dispute = await challengeResult(challenger, tournament, round, teamB, matchpMint.publicKey);
+      expect((await program.account.dispute.fetch(dispute)).quorum.toNumber()).to.equal(100);
+      expect(await balance(challengerMatchp)).to.equal(900);
+
+      // 投票期间治理配置锁定，开启治理的争议不能由管理员直接裁决
+      await expectError(configureGovernance(4, 1_000_000), "DisputeInProgress");
+      const ruling = rulingAccounts(tournament, round, teamA, teamB, challenger.publicKey, matchpMint.publicKey, challenger.publicKey);
+      await expectError(
+        program.methods
+          .resolveDispute(true)
+          .accounts({ arbiter: authority.publicKey, state: statePda, resolverRole: null, ruling } as any)
+          .signers([authority])
+          .rpc(),
+        "GovernanceVoteRequired"
+      );
+
+      // 赛事方锁定vote投票推翻
+      const disputeVote = pda(Buffer.from("dispute_vote"), dispute.toBuffer(), creator.publicKey.toBuffer());
+      const governanceVault = pda(Buffer.from("governance_vault"), dispute.toBuffer());
+      const voteBefore = await balance(creatorVote);
+      await program.methods
+        .castDisputeVote(true, new anchor.BN(500))
+        .accounts({
+          voter: creator.publicKey,
+          tournament,
+          dispute,
+          disputeVote,
+          voterVoteToken: creatorVote,
+          governanceVault,
+          voteMint: tournamentVoteMint,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        } as any)
+        .signers([creator])
+        .rpc();
+      expect(await balance(creatorVote)).to.equal(voteBefore - 500);
+
+      const finalize = () =>
+        program.methods
+          .finalizeDisputeVote()
+          .accounts({ caller: challenger.publicKey, ruling } as any)
+          .signers([challenger])
+          .rpc();
+      await expectError(finalize(), "VotingOpen");
+      await waitForTimestamp((await program.account.dispute.fetch(dispute)).voteDeadline.toNumber());
+      await finalize();
+
+      const overturned = await program.account.tournamentRound.fetch(round);
+      expect(overturned.winnerTeam.equals(teamB)).to.be.true;
+      expect((await program.account.dispute.fetch(dispute)).isOverturned).to.be.true;
+      expect(await balance(challengerMatchp)).to.equal(1000);
+
+      // 裁决后取回锁定的vote
+      await program.methods
+        .withdrawDisputeVote()
+        .accounts({
+          voter: creator.publicKey,
+          tournament,
+          dispute,
+          disputeVote,
+          voterVoteToken: creatorVote,
+          governanceVault,
+          voteMint: tournamentVoteMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([creator])
+        .rpc();
+      expect(await balance(creatorVote)).to.equal(voteBefore);
+    });
+  });
+
+  describe("外部结果预言机", () => {
+    const mockOracle = anchor.workspace.MockOracle as Program<MockOracle>;
+    let creator: anchor.web3.Keypair;
+    let publisher: anchor.web3.Keypair;
+    let attacker: anchor.web3.Keypair;
+    let tournament: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      publisher = await fundedKeypair();
+      attacker = await fundedKeypair();
+      tournament = await createTournament(creator, "预言机测试赛事");
+    });
+
+    const configureOracle = (maxStaleness: number) =>
+      program.methods
+        .configureOracle(mockOracle.programId, publisher.publicKey, new anchor.BN(maxStaleness))
+        .accounts({ authority: creator.publicKey, tournament } as any)
+        .signers([creator])
+        .rpc();
+
+    const oracleResult = (round: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("oracle_result"), round.toBuffer()], mockOracle.programId)[0];
+
+    const publish = (signer: anchor.web3.Keypair, round: PublicKey, team: PublicKey) =>
+      mockOracle.methods
+        .publishResult(round, team)
+        .accounts({
+          authority: signer.publicKey,
+          oracleResult: oracleResult(round),
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([signer])
+        .rpc();
+
+    it("拒绝错误所有者、非指定发布者和过期的结果", async () => {
+      await configureOracle(3);
+      // 预言机配置设置后不可修改
+      await expectError(configureOracle(3600), "OracleConfigLocked");
+
+      const round = await createRound(creator, tournament, 1);
+      const team = await createTeam(creator, tournament, round, "A");
+
+      // 必须提供预言机结果账户，且账户由指定的预言机程序拥有
+      await expectError(completeRound(creator, tournament, round, team), "OracleResultRequired");
+      await expectError(completeRound(creator, tournament, round, team, statePda), "InvalidOracleOwner");
+
+      // 他人抢先发布的结果不被接受
+      await publish(attacker, round, team);
+      await expectError(completeRound(creator, tournament, round, team, oracleResult(round)), "InvalidOraclePublisher");
+    });
+
+    it("指定发布者的最新结果可以完成轮次", async () => {
+      const round = await createRound(creator, tournament, 2);
+      const team = await createTeam(creator, tournament, round, "A");
+
+      // 超过有效期的结果不被接受
+      await publish(publisher, round, team);
+      const published = await mockOracle.account.oracleResult.fetch(oracleResult(round));
+      await waitForTimestamp(published.updatedAt.toNumber() + 4);
+      await expectError(completeRound(creator, tournament, round, team, oracleResult(round)), "StaleOracleResult");
+
+      // 发布者更新后可以完成轮次
+      await publish(publisher, round, team);
+      await completeRound(creator, tournament, round, team, oracleResult(round));
+      const completed = await program.account.tournamentRound.fetch(round);
+      expect(completed.isCompleted).to.be.true;
+      expect(completed.winnerTeam.equals(team)).to.be.true;
+    });
+  });
+
+  describe("赛事方质押罚没", () => {
+    let creator: anchor.web3.Keypair;
+    let challenger: anchor.web3.Keypair;
+    let bettor: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let tournamentVoteMint: PublicKey;
+    let round: PublicKey;
+    let teamA: PublicKey;
+    let teamB: PublicKey;
+    let dispute: PublicKey;
+    let placed: { bet: PublicKey; betMint: PublicKey };
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      tournament = await createTournament(creator, "罚没测试赛事", 10_000);
+      tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+      await program.methods
+        .configureDispute(new anchor.BN(10), new anchor.BN(100))
+        .accounts({ authority: creator.publicKey, tournament } as any)
+        .signers([creator])
+        .rpc();
+      // 结果被推翻时罚没10%的质押
+      await program.methods
+        .configureSlashing(1000, new anchor.BN(0))
+        .accounts({ authority: authority.publicKey, state: statePda, adminRole: null, tournament } as any)
+        .signers([authority])
+        .rpc();
+      round = await createRound(creator, tournament, 1);
+      teamA = await createTeam(creator, tournament, round, "A");
+      teamB = await createTeam(creator, tournament, round, "B");
+
+      challenger = await fundedKeypair();
+      await tokenAccount(matchpMint.publicKey, challenger.publicKey, 1000);
+      // 下注者从赛事方获得vote后下注
+      bettor = await fundedKeypair();
+      await tokenAccount(matchpMint.publicKey, bettor.publicKey);
+      const bettorVote = await tokenAccount(tournamentVoteMint, bettor.publicKey);
+      await transfer(
+        provider.connection,
+        creator,
+        getAssociatedTokenAddressSync(tournamentVoteMint, creator.publicKey),
+        bettorVote,
+        creator,
+        1000
+      );
+      placed = await placeBet(bettor, tournament, round, teamA, 1000);
+    });
+
+    const unstake = (amount: number) =>
+      program.methods
+        .unstakeTournament(new anchor.BN(amount))
+        .accounts({
+          authority: creator.publicKey,
+          tournament,
+          state: statePda,
+          authorityVoteToken: getAssociatedTokenAddressSync(tournamentVoteMint, creator.publicKey),
+          authorityMatchpToken: getAssociatedTokenAddressSync(matchpMint.publicKey, creator.publicKey),
+          tournamentMatchpToken: pda(Buffer.from("tournament_matchp"), tournament.toBuffer()),
+          voteMint: tournamentVoteMint,
+          stakePosition: pda(Buffer.from("stake_position"), tournament.toBuffer(), creator.publicKey.toBuffer()),
+          matchpMint: matchpMint.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([creator])
+        .rpc();
+
+    const closeTournament = () =>
+      program.methods
+        .closeTournament()
+        .accounts({
+          authority: creator.publicKey,
+          tournament,
+          state: statePda,
+          tournamentMatchpToken: pda(Buffer.from("tournament_matchp"), tournament.toBuffer()),
+          authorityMatchpToken: getAssociatedTokenAddressSync(matchpMint.publicKey, creator.publicKey),
+          tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+          currencyVault: null,
+          solVault: null,
+          authorityCurrencyToken: null,
+          voteMint: tournamentVoteMint,
+          stakePosition: pda(Buffer.from("stake_position"), tournament.toBuffer(), creator.publicKey.toBuffer()),
+          currencyMint: null,
+          matchpMint: matchpMint.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          currencyTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([creator])
+        .rpc();
+
+    it("轮次结果、争议或罚没未了结时不能关闭赛事或赎回质押", async () => {
+      // 轮次还没有结果
+      await expectError(unstake(100), "UnsettledRounds");
+      await expectError(closeTournament(), "UnsettledRounds");
+
+      await completeRound(creator, tournament, round, teamA);
+      dispute = await challengeResult(challenger, tournament, round, teamB, matchpMint.publicKey);
+      // 争议未裁决
+      await expectError(closeTournament(), "UnsettledRounds");
+
+      await program.methods
+        .resolveDispute(true)
+        .accounts({
+          arbiter: authority.publicKey,
+          state: statePda,
+          resolverRole: null,
+          ruling: rulingAccounts(tournament, round, teamA, teamB, challenger.publicKey, matchpMint.publicKey, authority.publicKey),
+        } as any)
+        .signers([authority])
+        .rpc();
+      expect((await program.account.tournamentRound.fetch(round)).slashPending).to.be.true;
+      expect((await program.account.tournament.fetch(tournament)).pendingSlashes).to.equal(1);
+      // 罚没待执行
+      await expectError(unstake(100), "UnsettledRounds");
+      await expectError(closeTournament(), "UnsettledRounds");
+      expect((await program.account.tournament.fetch(tournament)).isActive).to.be.true;
+    });
+
+    it("罚没只能从赛事的质押托管账户转出，且必须奖励争议发起人", async () => {
+      // 赛事PDA持有的其他matchp账户不是质押托管账户
+      const fakeVault = await getOrCreateAssociatedTokenAccount(provider.connection, authority, matchpMint.publicKey, tournament, true);
+      await mintTo(provider.connection, authority, matchpMint.publicKey, fakeVault.address, authority, 1000);
+      const challengerMatchp = getAssociatedTokenAddressSync(matchpMint.publicKey, challenger.publicKey);
+      await expectError(slashStake(bettor, tournament, round, dispute, challengerMatchp, fakeVault.address), "ConstraintSeeds");
+
+      await expectError(slashStake(bettor, tournament, round, null, null), "ChallengerAccountRequired");
+      await expectError(
+        slashStake(bettor, tournament, round, dispute, getAssociatedTokenAddressSync(matchpMint.publicKey, bettor.publicKey)),
+        "InvalidTokenOwner"
+      );
+      expect(await balance(fakeVault.address)).to.equal(1000);
+    });
+
+    it("罚没奖励发起人并补偿下注者，了结后可以关闭赛事", async () => {
+      const challengerMatchp = getAssociatedTokenAddressSync(matchpMint.publicKey, challenger.publicKey);
+      expect(await balance(challengerMatchp)).to.equal(1000);
+
+      // 罚没10%质押：20%奖励发起人，其余进入补偿池
+      await slashStake(bettor, tournament, round, dispute, challengerMatchp);
+      expect(await balance(challengerMatchp)).to.equal(1200);
+      const slashed = await program.account.tournamentRound.fetch(round);
+      expect(slashed.isSlashed).to.be.true;
+      expect(slashed.compensationPool.toNumber()).to.equal(800);
+      const afterSlash = await program.account.tournament.fetch(tournament);
+      expect(afterSlash.stakeAmount.toNumber()).to.equal(9000);
+      expect(afterSlash.pendingSlashes).to.equal(0);
+      await expectError(slashStake(bettor, tournament, round, dispute, challengerMatchp), "RoundAlreadySlashed");
+
+      // 下注者按下注比例领取补偿
+      await claimCompensation(bettor, tournament, round, placed);
+      expect(await balance(getAssociatedTokenAddressSync(matchpMint.publicKey, bettor.publicKey))).to.equal(800);
+      await expectError(claimCompensation(bettor, tournament, round, placed), "CompensationAlreadyClaimed");
+
+      // 争议期结束后赛事方取回剩余质押
+      await waitForTimestamp(afterSlash.disputeWindowEnd.toNumber());
+      const creatorMatchp = getAssociatedTokenAddressSync(matchpMint.publicKey, creator.publicKey);
+      const creatorMatchpBefore = await balance(creatorMatchp);
+      await closeTournament();
+      expect((await program.account.tournament.fetch(tournament)).isActive).to.be.false;
+      expect(await balance(creatorMatchp)).to.equal(creatorMatchpBefore + 9000);
+    });
+  });
+
+  describe("裁定期限与过期退款", () => {
+    let creator: anchor.web3.Keypair;
+    let bettor: anchor.web3.Keypair;
+    let bettorVote: PublicKey;
+    let tournament: PublicKey;
+    let tournamentVoteMint: PublicKey;
+    let round: PublicKey;
+    let team: PublicKey;
+    let market: PublicKey;
+    let placed: { bet: PublicKey; betMint: PublicKey };
+    let marketPlaced: { bet: PublicKey; betMint: PublicKey };
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      tournament = await createTournament(creator, "过期测试赛事", 10_000);
+      tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+      // 裁定期限在创建轮次时生效
+      await program.methods
+        .configureSlashing(1000, new anchor.BN(6))
+        .accounts({ authority: authority.publicKey, state: statePda, adminRole: null, tournament } as any)
+        .signers([authority])
+        .rpc();
+
+      bettor = await fundedKeypair();
+      await tokenAccount(matchpMint.publicKey, bettor.publicKey);
+      bettorVote = await tokenAccount(tournamentVoteMint, bettor.publicKey);
+      await transfer(
+        provider.connection,
+        creator,
+        getAssociatedTokenAddressSync(tournamentVoteMint, creator.publicKey),
+        bettorVote,
+        creator,
+        1500
+      );
+
+      round = await createRound(creator, tournament, 1);
+      team = await createTeam(creator, tournament, round, "A");
+      market = pda(Buffer.from("market"), round.toBuffer(), Buffer.from([0]));
+      await program.methods
+        .createMarket(0, "总分大小", { overUnder: {} } as any, new anchor.BN(91), [])
+        .accounts({
+          authority: creator.publicKey,
+          tournament,
+          round,
+          market,
+          favoriteTeam: null,
+          underdogTeam: null,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([creator])
+        .rpc();
+      placed = await placeBet(bettor, tournament, round, team, 1000);
+      marketPlaced = await placeMarketBet(bettor, tournament, round, market, 500, 0);
+    });
+
+    const expireRound = () =>
+      program.methods
+        .expireRound()
+        .accounts({ caller: bettor.publicKey, tournament, round } as any)
+        .signers([bettor])
+        .rpc();
+
+    const refundBet = (target: { bet: PublicKey; betMint: PublicKey }) =>
+      program.methods
+        .refundBet()
+        .accounts({
+          tournament,
+          round,
+          bet: target.bet,
+          user: bettor.publicKey,
+          userBetNft: getAssociatedTokenAddressSync(target.betMint, bettor.publicKey),
+          betMint: target.betMint,
+          userCurrencyToken: null,
+          userVoteToken: bettorVote,
+          currencyVault: null,
+          solVault: null,
+          tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+          currencyMint: null,
+          voteMint: tournamentVoteMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          currencyTokenProgram: TOKEN_PROGRAM_ID,
+          nftTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([bettor])
+        .rpc();
+
+    it("裁定期限前不能过期，未过期的轮次不能退款", async () => {
+      expect(await balance(bettorVote)).to.equal(0);
+      await expectError(expireRound(), "ResolutionDeadlineNotPassed");
+      await expectError(refundBet(placed), "RoundNotExpired");
+
+      // 市场在期限前已出结果，但轮次本身没有完成
+      await program.methods
+        .reportScore(new anchor.BN(50))
+        .accounts({ authority: creator.publicKey, tournament, market, round } as any)
+        .signers([creator])
+        .rpc();
+      expect((await program.account.market.fetch(market)).isResolved).to.be.true;
+    });
+
+    it("过期后退还队伍和市场下注本金，并罚没质押补偿下注者", async () => {
+      await waitForTimestamp((await program.account.tournamentRound.fetch(round)).resolutionDeadline.toNumber());
+      await expireRound();
+      const expired = await program.account.tournamentRound.fetch(round);
+      expect(expired.isExpired).to.be.true;
+      expect(expired.slashPending).to.be.true;
+
+      // 过期后赛事方不能再完成轮次
+      await expectError(completeRound(creator, tournament, round, team), "ConstraintRaw");
+
+      // 退还下注本金并销毁NFT，已出结果的市场下注同样退款
+      await refundBet(placed);
+      await refundBet(marketPlaced);
+      expect(await balance(bettorVote)).to.equal(1500);
+      expect((await program.account.bet.fetch(placed.bet)).isSettled).to.be.true;
+      expect((await program.account.bet.fetch(marketPlaced.bet)).isSettled).to.be.true;
+      await expectError(refundBet(placed), "AccountNotInitialized");
+
+      // 过期导致的罚没无需争议账户，全部进入补偿池
+      await slashStake(bettor, tournament, round, null, null);
+      expect((await program.account.tournamentRound.fetch(round)).compensationPool.toNumber()).to.equal(1000);
+
+      // 已退款的下注凭下注记录领取补偿
+      await claimCompensation(bettor, tournament, round, placed, null);
+      expect(await balance(getAssociatedTokenAddressSync(matchpMint.publicKey, bettor.publicKey))).to.equal(1000);
+    });
+  });
+
+  describe("赛事专属vote代币", () => {
+    let creatorA: anchor.web3.Keypair;
+    let creatorB: anchor.web3.Keypair;
+    let tournamentA: PublicKey;
+    let tournamentB: PublicKey;
+    let roundB: PublicKey;
+    let teamB: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creatorA = await fundedKeypair();
+      creatorB = await fundedKeypair();
+      tournamentA = await createTournament(creatorA, "vote隔离赛事A", 5_000);
+      tournamentB = await createTournament(creatorB, "vote隔离赛事B", 5_000);
+      roundB = await createRound(creatorB, tournamentB, 1);
+      teamB = await createTeam(creatorB, tournamentB, roundB, "A");
+    });
+
+    // 使用指定的vote代币账户和铸造器在赛事B下注
+    const placeBetWith = (user: anchor.web3.Keypair, userVoteToken: PublicKey, voteMint: PublicKey) => {
+      const bet = anchor.web3.Keypair.generate();
+      return program.methods
+        .placeBet(new anchor.BN(100))
+        .accounts({
+          user: user.publicKey,
+          tournament: tournamentB,
+          state: statePda,
+          round: roundB,
+          team: teamB,
+          bet: bet.publicKey,
+          userCurrencyToken: null,
+          userVoteToken,
+          currencyVault: null,
+          solVault: null,
+          tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournamentB.toBuffer()),
+          currencyMint: null,
+          voteMint,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          currencyTokenProgram: TOKEN_PROGRAM_ID,
+          nftTokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        } as any)
+        .signers([user, bet])
+        .rpc();
+    };
+
+    it("每个赛事使用独立的vote代币，铸币权限为赛事PDA", async () => {
+      const voteMintA = pda(Buffer.from("vote_mint"), tournamentA.toBuffer());
+      const voteMintB = pda(Buffer.from("vote_mint"), tournamentB.toBuffer());
+      expect((await program.account.tournament.fetch(tournamentA)).voteMint.equals(voteMintA)).to.be.true;
+      expect((await program.account.tournament.fetch(tournamentB)).voteMint.equals(voteMintB)).to.be.true;
+      expect((await getMint(provider.connection, voteMintA)).mintAuthority?.equals(tournamentA)).to.be.true;
+      expect((await getMint(provider.connection, voteMintB)).mintAuthority?.equals(tournamentB)).to.be.true;
+      expect(await balance(getAssociatedTokenAddressSync(voteMintA, creatorA.publicKey))).to.equal(5_000);
+      expect(await balance(getAssociatedTokenAddressSync(voteMintB, creatorB.publicKey))).to.equal(5_000);
+
+      // 赛事B的vote代币可以在赛事B下注
+      await placeBetWith(
+        creatorB,
+        getAssociatedTokenAddressSync(voteMintB, creatorB.publicKey),
+        voteMintB
+      );
+      expect(await balance(pda(Buffer.from("tournament_vote"), tournamentB.toBuffer()))).to.equal(100);
+    });
+
+    it("其他赛事的vote代币不能在本赛事下注", async () => {
+      const voteMintA = pda(Buffer.from("vote_mint"), tournamentA.toBuffer());
+      const voteMintB = pda(Buffer.from("vote_mint"), tournamentB.toBuffer());
+      const userVoteA = getAssociatedTokenAddressSync(voteMintA, creatorA.publicKey);
+
+      await expectError(placeBetWith(creatorA, userVoteA, voteMintB), "InvalidTokenMint");
+      expect(await balance(userVoteA)).to.equal(5_000);
+      expect(await balance(pda(Buffer.from("tournament_vote"), tournamentB.toBuffer()))).to.equal(100);
+    });
+  });
+
+  describe("追加质押与部分赎回", () => {
+    let creator: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let tournamentVoteMint: PublicKey;
+    let creatorMatchp: PublicKey;
+    let creatorVote: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      tournament = await createTournament(creator, "追加质押赛事", 5_000);
+      tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+      creatorMatchp = getAssociatedTokenAddressSync(matchpMint.publicKey, creator.publicKey);
+      creatorVote = getAssociatedTokenAddressSync(tournamentVoteMint, creator.publicKey);
+    });
+
+    const stake = (amount: number) =>
+      program.methods
+        .stakeTournament(new anchor.BN(amount))
+        .accounts({
+          authority: creator.publicKey,
+          tournament,
+          state: statePda,
+          authorityMatchpToken: creatorMatchp,
+          authorityVoteToken: creatorVote,
+          tournamentMatchpToken: pda(Buffer.from("tournament_matchp"), tournament.toBuffer()),
+          matchpMint: matchpMint.publicKey,
+          voteMint: tournamentVoteMint,
+          stakePosition: pda(Buffer.from("stake_position"), tournament.toBuffer(), creator.publicKey.toBuffer()),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([creator])
+        .rpc();
+
+    const unstake = (amount: number) =>
+      program.methods
+        .unstakeTournament(new anchor.BN(amount))
+        .accounts({
+          authority: creator.publicKey,
+          tournament,
+          state: statePda,
+          authorityVoteToken: creatorVote,
+          authorityMatchpToken: creatorMatchp,
+          tournamentMatchpToken: pda(Buffer.from("tournament_matchp"), tournament.toBuffer()),
+          voteMint: tournamentVoteMint,
+          stakePosition: pda(Buffer.from("stake_position"), tournament.toBuffer(), creator.publicKey.toBuffer()),
+          matchpMint: matchpMint.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([creator])
+        .rpc();
+
+    it("已质押的赛事可以追加质押", async () => {
+      await tokenAccount(matchpMint.publicKey, creator.publicKey, 3_000);
+      await stake(3_000);
+
+      const account = await program.account.tournament.fetch(tournament);
+      expect(account.isStaked).to.be.true;
+      expect(account.stakeAmount.toNumber()).to.equal(8_000);
+      expect(await balance(creatorVote)).to.equal(8_000);
+      expect(await balance(pda(Buffer.from("tournament_matchp"), tournament.toBuffer()))).to.equal(8_000);
+
+      await expectError(stake(0), "InvalidStakeAmount");
+    });
+
+    it("赎回部分质押，销毁等量vote并取回matchp", async () => {
+      await unstake(3_000);
+
+      const account = await program.account.tournament.fetch(tournament);
+      expect(account.isStaked).to.be.true;
+      expect(account.stakeAmount.toNumber()).to.equal(5_000);
+      expect(await balance(creatorVote)).to.equal(5_000);
+      expect(await balance(creatorMatchp)).to.equal(3_000);
+      expect((await getMint(provider.connection, tournamentVoteMint)).supply).to.equal(BigInt(5_000));
+    });
+
+    it("赎回数量必须大于0且不超过赛事方份额的价值", async () => {
+      await expectError(unstake(0), "InvalidStakeAmount");
+      await expectError(unstake(5_001), "InvalidStakeAmount");
+      expect((await program.account.tournament.fetch(tournament)).stakeAmount.toNumber()).to.equal(5_000);
+    });
+  });
+
+  describe("vote分发与兑换", () => {
+    let creator: anchor.web3.Keypair;
+    let player: anchor.web3.Keypair;
+    let outsider: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let tournamentVoteMint: PublicKey;
+    let voteDistribution: PublicKey;
+    let distributionVault: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      player = await fundedKeypair();
+      outsider = await fundedKeypair();
+      tournament = await createTournament(creator, "vote分发赛事", 10_000);
+      tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+      voteDistribution = pda(Buffer.from("vote_distribution"), tournament.toBuffer());
+      distributionVault = pda(Buffer.from("vote_distribution_vault"), tournament.toBuffer());
+      await tokenAccount(wanziMint.publicKey, creator.publicKey);
+      await tokenAccount(wanziMint.publicKey, player.publicKey, 2_000);
+      await tokenAccount(tournamentVoteMint, player.publicKey);
+      await tokenAccount(tournamentVoteMint, outsider.publicKey);
+    });
+
+    const configure = (swapRateBps: number, playerAllowance: number) =>
+      program.methods
+        .configureVoteDistribution(new anchor.BN(swapRateBps), new anchor.BN(playerAllowance))
+        .accounts({
+          authority: creator.publicKey,
+          tournament,
+          voteDistribution,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([creator])
+        .rpc();
+
+    const claimAllowance = (claimer: anchor.web3.Keypair) =>
+      program.methods
+        .claimVoteAllowance()
+        .accounts({
+          player: claimer.publicKey,
+          tournament,
+          voteDistribution,
+          playerRegistration: pda(Buffer.from("player"), tournament.toBuffer(), claimer.publicKey.toBuffer()),
+          playerVoteToken: getAssociatedTokenAddressSync(tournamentVoteMint, claimer.publicKey),
+          distributionVault,
+          voteMint: tournamentVoteMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([claimer])
+        .rpc();
+
+    const swap = (wanziAmount: number) =>
+      program.methods
+        .swapWanziForVote(new anchor.BN(wanziAmount))
+        .accounts({
+          player: player.publicKey,
+          state: statePda,
+          tournament,
+          voteDistribution,
+          playerWanziToken: getAssociatedTokenAddressSync(wanziMint.publicKey, player.publicKey),
+          authorityWanziToken: getAssociatedTokenAddressSync(wanziMint.publicKey, creator.publicKey),
+          playerVoteToken: getAssociatedTokenAddressSync(tournamentVoteMint, player.publicKey),
+          distributionVault,
+          wanziMint: wanziMint.publicKey,
+          voteMint: tournamentVoteMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([player])
+        .rpc();
+
+    it("注册玩家领取一次vote额度", async () => {
+      await configure(5000, 300);
+      await program.methods
+        .fundVoteDistribution(new anchor.BN(2_000))
+        .accounts({
+          authority: creator.publicKey,
+          tournament,
+          voteDistribution,
+          authorityVoteToken: getAssociatedTokenAddressSync(tournamentVoteMint, creator.publicKey),
+          distributionVault,
+          voteMint: tournamentVoteMint,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        } as any)
+        .signers([creator])
+        .rpc();
+      expect(await balance(distributionVault)).to.equal(2_000);
+
+      await program.methods
+        .registerPlayer(player.publicKey)
+        .accounts({
+          authority: creator.publicKey,
+          tournament,
+          playerRegistration: pda(Buffer.from("player"), tournament.toBuffer(), player.publicKey.toBuffer()),
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([creator])
+        .rpc();
+
+      await claimAllowance(player);
+      expect(await balance(getAssociatedTokenAddressSync(tournamentVoteMint, player.publicKey))).to.equal(300);
+      expect(await balance(distributionVault)).to.equal(1_700);
+      expect((await program.account.playerRegistration.fetch(
+        pda(Buffer.from("player"), tournament.toBuffer(), player.publicKey.toBuffer())
+      )).hasClaimed).to.be.true;
+
+      // 每位玩家只能领取一次，未注册的玩家不能领取
+      await expectError(claimAllowance(player), "AllowanceAlreadyClaimed");
+      await expectError(claimAllowance(outsider), "AccountNotInitialized");
+    });
+
+    it("玩家按比例用wanzi兑换vote，wanzi支付给赛事管理员", async () => {
+      await swap(1_000);
+      expect(await balance(getAssociatedTokenAddressSync(tournamentVoteMint, player.publicKey))).to.equal(800);
+      expect(await balance(getAssociatedTokenAddressSync(wanziMint.publicKey, player.publicKey))).to.equal(1_000);
+      expect(await balance(getAssociatedTokenAddressSync(wanziMint.publicKey, creator.publicKey))).to.equal(1_000);
+
+      const distribution = await program.account.voteDistribution.fetch(voteDistribution);
+      expect(distribution.totalSwapped.toNumber()).to.equal(500);
+      expect(distribution.totalClaimed.toNumber()).to.equal(300);
+    });
+
+    it("兑换比例为0时关闭兑换", async () => {
+      await configure(0, 300);
+      await expectError(swap(1_000), "VoteDistributionDisabled");
+      expect(await balance(getAssociatedTokenAddressSync(wanziMint.publicKey, player.publicKey))).to.equal(1_000);
+    });
+  });
+
+  describe("质押奖励手续费", () => {
+    let creator: anchor.web3.Keypair;
+    let bettor: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let tournamentVoteMint: PublicKey;
+    let rewardsVault: PublicKey;
+    let creatorVote: PublicKey;
+    let bettorVote: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      bettor = await fundedKeypair();
+      tournament = await createTournament(creator, "手续费赛事", 10_000);
+      tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+      rewardsVault = pda(Buffer.from("rewards_vault"), tournament.toBuffer());
+      creatorVote = getAssociatedTokenAddressSync(tournamentVoteMint, creator.publicKey);
+      bettorVote = await tokenAccount(tournamentVoteMint, bettor.publicKey);
+      await transfer(provider.connection, creator, creatorVote, bettorVote, creator, 1_000);
+    });
+
+    const configureFees = (feeBps: number) =>
+      program.methods
+        .configureFees(feeBps)
+        .accounts({
+          authority: creator.publicKey,
+          tournament,
+          rewardsVault,
+          voteMint: tournamentVoteMint,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        } as any)
+        .signers([creator])
+        .rpc();
+
+    const claimRewards = () =>
+      program.methods
+        .claimStakeRewards()
+        .accounts({
+          owner: creator.publicKey,
+          tournament,
+          stakePosition: pda(Buffer.from("stake_position"), tournament.toBuffer(), creator.publicKey.toBuffer()),
+          ownerVoteToken: creatorVote,
+          rewardsVault,
+          voteMint: tournamentVoteMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([creator])
+        .rpc();
+
+    it("手续费不能超过上限", async () => {
+      await expectError(configureFees(1001), "InvalidFeeConfig");
+      await configureFees(500);
+      expect((await program.account.tournament.fetch(tournament)).feeBps).to.equal(500);
+    });
+
+    it("从获胜奖金中扣除手续费，质押者领取奖励", async () => {
+      const round = await createRound(creator, tournament, 1);
+      const team = await createTeam(creator, tournament, round, "A");
+      const placed = await placeBet(bettor, tournament, round, team, 1_000);
+
+      // 有下注后手续费锁定
+      await expectError(configureFees(300), "FeesLocked");
+      expect((await program.account.tournament.fetch(tournament)).feeBps).to.equal(500);
+
+      await completeRound(creator, tournament, round, team);
+      await program.methods
+        .settleBet()
+        .accounts({
+          tournament,
+          round,
+          winnerTeam: team,
+          bet: placed.bet,
+          user: bettor.publicKey,
+          userBetNft: getAssociatedTokenAddressSync(placed.betMint, bettor.publicKey),
+          betMint: placed.betMint,
+          userCurrencyToken: null,
+          userVoteToken: bettorVote,
+          currencyVault: null,
+          solVault: null,
+          tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+          rewardsVault,
+          currencyMint: null,
+          voteMint: tournamentVoteMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          currencyTokenProgram: TOKEN_PROGRAM_ID,
+          nftTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([bettor])
+        .rpc();
+      expect(await balance(bettorVote)).to.equal(950);
+      expect(await balance(rewardsVault)).to.equal(50);
+
+      // 赛事方持有全部份额，领取全部手续费
+      await claimRewards();
+      expect(await balance(creatorVote)).to.equal(9_050);
+      expect(await balance(rewardsVault)).to.equal(0);
+      await expectError(claimRewards(), "NoRewards");
+    });
+  });
+
+  describe("委托质押", () => {
+    let creator: anchor.web3.Keypair;
+    let delegator: anchor.web3.Keypair;
+    let receiver: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let receiptMint: PublicKey;
+    let delegatorMatchp: PublicKey;
+    let delegatorReceipt: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      delegator = await fundedKeypair();
+      receiver = await fundedKeypair();
+      tournament = await createTournament(creator, "委托质押赛事", 10_000);
+      receiptMint = pda(Buffer.from("stake_receipt"), tournament.toBuffer());
+      delegatorMatchp = await tokenAccount(matchpMint.publicKey, delegator.publicKey, 5_000);
+      delegatorReceipt = getAssociatedTokenAddressSync(receiptMint, delegator.publicKey);
+    });
+
+    const delegate = (staker: anchor.web3.Keypair, amount: number) =>
+      program.methods
+        .delegateStake(new anchor.BN(amount))
+        .accounts({
+          delegator: staker.publicKey,
+          state: statePda,
+          tournament,
+          stakePosition: pda(Buffer.from("stake_position"), tournament.toBuffer(), staker.publicKey.toBuffer()),
+          delegatorMatchpToken: getAssociatedTokenAddressSync(matchpMint.publicKey, staker.publicKey),
+          tournamentMatchpToken: pda(Buffer.from("tournament_matchp"), tournament.toBuffer()),
+          receiptMint,
+          delegatorReceiptToken: getAssociatedTokenAddressSync(receiptMint, staker.publicKey),
+          matchpMint: matchpMint.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        } as any)
+        .signers([staker])
+        .rpc();
+
+    const undelegate = (shares: number) =>
+      program.methods
+        .undelegateStake(new anchor.BN(shares))
+        .accounts({
+          delegator: delegator.publicKey,
+          tournament,
+          state: statePda,
+          stakePosition: pda(Buffer.from("stake_position"), tournament.toBuffer(), delegator.publicKey.toBuffer()),
+          delegatorMatchpToken: delegatorMatchp,
+          tournamentMatchpToken: pda(Buffer.from("tournament_matchp"), tournament.toBuffer()),
+          receiptMint,
+          delegatorReceiptToken: delegatorReceipt,
+          matchpMint: matchpMint.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([delegator])
+        .rpc();
+
+    it("委托matchp获得份额和冻结的质押凭证", async () => {
+      await delegate(delegator, 5_000);
+
+      const account = await program.account.tournament.fetch(tournament);
+      expect(account.stakeAmount.toNumber()).to.equal(15_000);
+      expect(account.rewardShares.toNumber()).to.equal(15_000);
+      const position = await program.account.stakePosition.fetch(
+        pda(Buffer.from("stake_position"), tournament.toBuffer(), delegator.publicKey.toBuffer())
+      );
+      expect(position.shares.toNumber()).to.equal(5_000);
+      expect(await balance(delegatorMatchp)).to.equal(0);
+
+      const receipt = await getAccount(provider.connection, delegatorReceipt);
+      expect(Number(receipt.amount)).to.equal(5_000);
+      expect(receipt.isFrozen).to.be.true;
+    });
+
+    it("质押凭证不能转让，赛事方不能委托给自己的赛事", async () => {
+      const receiverReceipt = await tokenAccount(receiptMint, receiver.publicKey);
+      // 冻结账户转出失败(AccountFrozen)
+      await expectError(
+        transfer(provider.connection, delegator, delegatorReceipt, receiverReceipt, delegator, 1_000),
+        "0x11"
+      );
+      expect(await balance(delegatorReceipt)).to.equal(5_000);
+      expect(await balance(receiverReceipt)).to.equal(0);
+
+      await tokenAccount(matchpMint.publicKey, creator.publicKey, 1_000);
+      await expectError(delegate(creator, 1_000), "InvalidDelegator");
+    });
+
+    it("委托者按份额取回matchp，剩余凭证仍然冻结", async () => {
+      await expectError(undelegate(5_001), "InvalidStakeAmount");
+
+      await undelegate(2_000);
+      expect(await balance(delegatorMatchp)).to.equal(2_000);
+      const receipt = await getAccount(provider.connection, delegatorReceipt);
+      expect(Number(receipt.amount)).to.equal(3_000);
+      expect(receipt.isFrozen).to.be.true;
+      expect((await program.account.tournament.fetch(tournament)).stakeAmount.toNumber()).to.equal(13_000);
+    });
+  });
+
+  describe("Token-2022转账手续费下注代币", () => {
+    let creator: anchor.web3.Keypair;
+    let bettor: anchor.web3.Keypair;
+    let feeMint: anchor.web3.Keypair;
+    let bettorToken: PublicKey;
+    let tournament: PublicKey;
+    let round: PublicKey;
+    let team: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      bettor = await fundedKeypair();
+
+      // 创建每笔转账收取1%手续费的Token-2022代币
+      feeMint = anchor.web3.Keypair.generate();
+      const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+      const tx = new anchor.web3.Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: provider.wallet.publicKey,
+          newAccountPubkey: feeMint.publicKey,
+          space: mintLen,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          feeMint.publicKey,
+          authority.publicKey,
+          authority.publicKey,
+          100,
+          BigInt(1_000_000),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(feeMint.publicKey, 9, authority.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      );
+      await provider.sendAndConfirm(tx, [feeMint]);
+
+      await allowCurrency(feeMint.publicKey);
+      tournament = await createTournament(creator, "转账手续费赛事", 0, feeMint.publicKey);
+      round = await createRound(creator, tournament, 1);
+      team = await createTeam(creator, tournament, round, "A");
+      bettorToken = await tokenAccount(feeMint.publicKey, bettor.publicKey, 10_000, TOKEN_2022_PROGRAM_ID);
+    });
+
+    it("下注按托管账户实际到账数量记录，结算使用Token-2022程序", async () => {
+      expect((await program.account.tournament.fetch(tournament)).currencyMint.equals(feeMint.publicKey)).to.be.true;
+
+      const placed = await placeBet(bettor, tournament, round, team, 10_000);
+      const currencyVault = pda(Buffer.from("currency_vault"), tournament.toBuffer());
+      expect((await program.account.bet.fetch(placed.bet)).amount.toNumber()).to.equal(9_900);
+      expect((await program.account.tournamentRound.fetch(round)).totalCurrencyBets.toNumber()).to.equal(9_900);
+      expect(await balance(currencyVault)).to.equal(9_900);
+      expect(await balance(bettorToken)).to.equal(0);
+
+      await completeRound(creator, tournament, round, team);
+      await program.methods
+        .settleBet()
+        .accounts({
+          tournament,
+          round,
+          winnerTeam: team,
+          bet: placed.bet,
+          user: bettor.publicKey,
+          userBetNft: getAssociatedTokenAddressSync(placed.betMint, bettor.publicKey),
+          betMint: placed.betMint,
+          userCurrencyToken: bettorToken,
+          userVoteToken: await tokenAccount(pda(Buffer.from("vote_mint"), tournament.toBuffer()), bettor.publicKey),
+          currencyVault,
+          solVault: null,
+          tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+          rewardsVault: null,
+          currencyMint: feeMint.publicKey,
+          voteMint: pda(Buffer.from("vote_mint"), tournament.toBuffer()),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          currencyTokenProgram: TOKEN_2022_PROGRAM_ID,
+          nftTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([bettor])
+        .rpc();
+      // 赢家取回托管账户中的全部下注，转出时再扣除1%手续费
+      expect(await balance(currencyVault)).to.equal(0);
+      expect(await balance(bettorToken)).to.equal(9_801);
+    });
+
+    it("下注代币程序与铸造器不匹配时拒绝", async () => {
+      const nextRound = await createRound(creator, tournament, 2);
+      const nextTeam = await createTeam(creator, tournament, nextRound, "A");
+      const tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+      const bet = anchor.web3.Keypair.generate();
+      await expectError(
+        program.methods
+          .placeBet(new anchor.BN(1_000))
+          .accounts({
+            user: bettor.publicKey,
+            tournament,
+            state: statePda,
+            round: nextRound,
+            team: nextTeam,
+            bet: bet.publicKey,
+            userCurrencyToken: bettorToken,
+            userVoteToken: getAssociatedTokenAddressSync(tournamentVoteMint, bettor.publicKey),
+            currencyVault: pda(Buffer.from("currency_vault"), tournament.toBuffer()),
+            solVault: null,
+            tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+            currencyMint: feeMint.publicKey,
+            voteMint: tournamentVoteMint,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            currencyTokenProgram: TOKEN_PROGRAM_ID,
+            nftTokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+          } as any)
+          .signers([bettor, bet])
+          .rpc(),
+        "TokenProgram"
+      );
+      expect(await balance(bettorToken)).to.equal(9_801);
+    });
+  });
+
+  describe("下注代币白名单", () => {
+    let listedMint: PublicKey;
+    let unlistedMint: PublicKey;
+    const currencyAllowlist = pda(Buffer.from("currency_allowlist"));
+
+    before(async () => {
+      await ensureState();
+      listedMint = await createMint(provider.connection, authority, authority.publicKey, null, 9);
+      unlistedMint = await createMint(provider.connection, authority, authority.publicKey, null, 9);
+    });
+
+    const removeCurrency = (mint: PublicKey) =>
+      program.methods
+        .removeCurrency(mint)
+        .accounts({ authority: authority.publicKey, state: statePda, adminRole: null, currencyAllowlist } as any)
+        .signers([authority])
+        .rpc();
+
+    it("白名单中的代币可以作为下注代币", async () => {
+      await allowCurrency(listedMint);
+      expect((await program.account.currencyAllowlist.fetch(currencyAllowlist)).mints.some((mint) => mint.equals(listedMint))).to.be.true;
+
+      const creator = await fundedKeypair();
+      const tournament = await createTournament(creator, "白名单代币赛事", 0, listedMint);
+      expect((await program.account.tournament.fetch(tournament)).currencyMint.equals(listedMint)).to.be.true;
+
+      // 重复添加和非管理员添加都被拒绝
+      await expectError(
+        program.methods
+          .addCurrency(listedMint)
+          .accounts({
+            authority: authority.publicKey,
+            state: statePda,
+            adminRole: null,
+            currencyAllowlist,
+            systemProgram: SystemProgram.programId,
+          } as any)
+          .signers([authority])
+          .rpc(),
+        "CurrencyAlreadyAllowed"
+      );
+      const outsider = await fundedKeypair();
+      await expectError(
+        program.methods
+          .addCurrency(unlistedMint)
+          .accounts({
+            authority: outsider.publicKey,
+            state: statePda,
+            adminRole: null,
+            currencyAllowlist,
+            systemProgram: SystemProgram.programId,
+          } as any)
+          .signers([outsider])
+          .rpc(),
+        "MissingRole"
+      );
+    });
+
+    it("不在白名单中的代币不能作为下注代币", async () => {
+      await expectError(createTournament(await fundedKeypair(), "未列入代币赛事", 0, unlistedMint), "CurrencyNotAllowed");
+
+      // 移出白名单后不能再用于新赛事
+      await removeCurrency(listedMint);
+      await expectError(createTournament(await fundedKeypair(), "已移除代币赛事", 0, listedMint), "CurrencyNotAllowed");
+      await expectError(removeCurrency(listedMint), "CurrencyNotAllowed");
+    });
+  });
+
+  describe("SOL下注赛事", () => {
+    let creator: anchor.web3.Keypair;
+    let winner: anchor.web3.Keypair;
+    let loser: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let solVault: PublicKey;
+    let round: PublicKey;
+    let teamA: PublicKey;
+    let teamB: PublicKey;
+    const stake = LAMPORTS_PER_SOL / 2;
+
+    before(async () => {
+      await ensureState();
+      // 原生SOL需先加入下注代币白名单
+      await allowCurrency(NATIVE_MINT);
+      creator = await fundedKeypair();
+      winner = await fundedKeypair();
+      loser = await fundedKeypair();
+      tournament = await createTournament(creator, "SOL赛事", 0, NATIVE_MINT);
+      solVault = pda(Buffer.from("sol_vault"), tournament.toBuffer());
+      round = await createRound(creator, tournament, 1);
+      teamA = await createTeam(creator, tournament, round, "A");
+      teamB = await createTeam(creator, tournament, round, "B");
+    });
+
+    const settle = (placed: { bet: PublicKey; betMint: PublicKey }, vault: PublicKey | null) =>
+      program.methods
+        .settleBet()
+        .accounts({
+          tournament,
+          round,
+          winnerTeam: teamA,
+          bet: placed.bet,
+          user: winner.publicKey,
+          userBetNft: getAssociatedTokenAddressSync(placed.betMint, winner.publicKey),
+          betMint: placed.betMint,
+          userCurrencyToken: null,
+          userVoteToken: getAssociatedTokenAddressSync(pda(Buffer.from("vote_mint"), tournament.toBuffer()), winner.publicKey),
+          currencyVault: null,
+          solVault: vault,
+          tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+          rewardsVault: null,
+          currencyMint: null,
+          voteMint: pda(Buffer.from("vote_mint"), tournament.toBuffer()),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          currencyTokenProgram: TOKEN_PROGRAM_ID,
+          nftTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([winner])
+        .rpc();
+
+    it("SOL下注转入托管账户，获胜者从托管账户取回全部彩池", async () => {
+      const winnerPlaced = await placeBet(winner, tournament, round, teamA, stake);
+      await placeBet(loser, tournament, round, teamB, stake);
+      const vaultBalance = await provider.connection.getBalance(solVault);
+      expect(vaultBalance).to.be.at.least(2 * stake);
+      expect((await program.account.tournamentRound.fetch(round)).totalCurrencyBets.toNumber()).to.equal(2 * stake);
+      expect((await program.account.bet.fetch(winnerPlaced.bet)).amount.toNumber()).to.equal(stake);
+
+      await completeRound(creator, tournament, round, teamA);
+
+      // SOL赛事结算必须提供托管账户
+      await expectError(settle(winnerPlaced, null), "SolVaultRequired");
+
+      const winnerBefore = await provider.connection.getBalance(winner.publicKey);
+      await settle(winnerPlaced, solVault);
+      const winnerAfter = await provider.connection.getBalance(winner.publicKey);
+      expect(winnerAfter - winnerBefore).to.be.greaterThan(2 * stake - LAMPORTS_PER_SOL / 100);
+      expect(await provider.connection.getBalance(solVault)).to.equal(vaultBalance - 2 * stake);
+    });
+  });
+
+  describe("代币账户与铸造器校验", () => {
+    let creator: anchor.web3.Keypair;
+    let bettor: anchor.web3.Keypair;
+    let other: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let round: PublicKey;
+    let team: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      bettor = await fundedKeypair();
+      other = await fundedKeypair();
+      tournament = await createTournament(creator, "账户校验赛事");
+      round = await createRound(creator, tournament, 1);
+      team = await createTeam(creator, tournament, round, "A");
+      await tokenAccount(wanziMint.publicKey, bettor.publicKey, 1_000);
+      await tokenAccount(wanziMint.publicKey, other.publicKey, 1_000);
+      await tokenAccount(matchpMint.publicKey, bettor.publicKey, 1_000);
+    });
+
+    // 使用指定的下注代币账户下注
+    const placeBetFrom = (userCurrencyToken: PublicKey) => {
+      const bet = anchor.web3.Keypair.generate();
+      const tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+      return tokenAccount(tournamentVoteMint, bettor.publicKey).then((userVoteToken) =>
+        program.methods
+          .placeBet(new anchor.BN(100))
+          .accounts({
+            user: bettor.publicKey,
+            tournament,
+            state: statePda,
+            round,
+            team,
+            bet: bet.publicKey,
+            userCurrencyToken,
+            userVoteToken,
+            currencyVault: pda(Buffer.from("currency_vault"), tournament.toBuffer()),
+            solVault: null,
+            tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+            currencyMint: wanziMint.publicKey,
+            voteMint: tournamentVoteMint,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            currencyTokenProgram: TOKEN_PROGRAM_ID,
+            nftTokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+          } as any)
+          .signers([bettor, bet])
+          .rpc()
+      );
+    };
+
+    it("使用本人的下注代币账户下注", async () => {
+      await placeBetFrom(getAssociatedTokenAddressSync(wanziMint.publicKey, bettor.publicKey));
+      expect(await balance(getAssociatedTokenAddressSync(wanziMint.publicKey, bettor.publicKey))).to.equal(900);
+      expect(await balance(pda(Buffer.from("currency_vault"), tournament.toBuffer()))).to.equal(100);
+    });
+
+    it("拒绝他人的代币账户和错误铸造器的代币账户", async () => {
+      await expectError(
+        placeBetFrom(getAssociatedTokenAddressSync(wanziMint.publicKey, other.publicKey)),
+        "InvalidTokenOwner"
+      );
+      await expectError(
+        placeBetFrom(getAssociatedTokenAddressSync(matchpMint.publicKey, bettor.publicKey)),
+        "InvalidTokenMint"
+      );
+      expect(await balance(getAssociatedTokenAddressSync(wanziMint.publicKey, other.publicKey))).to.equal(1_000);
+      expect(await balance(pda(Buffer.from("currency_vault"), tournament.toBuffer()))).to.equal(100);
+    });
+
+    it("创建赛事时matchp铸造器必须与合约状态一致", async () => {
+      const fakeMatchp = await createMint(provider.connection, authority, authority.publicKey, null, 9);
+      const organizer = await fundedKeypair();
+      const fakeTournament = pda(Buffer.from("tournament"), organizer.publicKey.toBuffer());
+      const fakeVoteMint = pda(Buffer.from("vote_mint"), fakeTournament.toBuffer());
+      await expectError(
+        program.methods
+          .createTournament("错误matchp赛事", new anchor.BN(0))
+          .accounts({
+            authority: organizer.publicKey,
+            tournament: fakeTournament,
+            state: statePda,
+            operatorRole: null,
+            currencyMint: wanziMint.publicKey,
+            currencyAllowlist: null,
+            matchpMint: fakeMatchp,
+            voteMint: fakeVoteMint,
+            authorityMatchpToken: await tokenAccount(fakeMatchp, organizer.publicKey),
+            authorityVoteToken: getAssociatedTokenAddressSync(fakeVoteMint, organizer.publicKey),
+            tournamentMatchpToken: pda(Buffer.from("tournament_matchp"), fakeTournament.toBuffer()),
+            tournamentVoteToken: pda(Buffer.from("tournament_vote"), fakeTournament.toBuffer()),
+            stakePosition: pda(Buffer.from("stake_position"), fakeTournament.toBuffer(), organizer.publicKey.toBuffer()),
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            currencyTokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+          } as any)
+          .signers([organizer])
+          .rpc(),
+        "InvalidMatchpMint"
+      );
+    });
+  });
+
+  describe("合约管理员两步转移", () => {
+    let newAuthority: anchor.web3.Keypair;
+    let stranger: anchor.web3.Keypair;
+
+    before(async () => {
+      await ensureState();
+      newAuthority = await fundedKeypair();
+      stranger = await fundedKeypair();
+    });
+
+    // 测试结束时确保管理员转回authority，后续测试继续使用authority
+    after(async () => {
+      const state = await program.account.state.fetch(statePda);
+      if (state.authority.equals(newAuthority.publicKey)) {
+        await propose(newAuthority, authority.publicKey);
+        await accept(authority);
+      }
+    });
+
+    const propose = (signer: anchor.web3.Keypair, pending: PublicKey) =>
+      program.methods
+        .proposeAuthority(pending)
+        .accounts({ authority: signer.publicKey, state: statePda } as any)
+        .signers([signer])
+        .rpc();
+
+    const accept = (signer: anchor.web3.Keypair) =>
+      program.methods
+        .acceptAuthority()
+        .accounts({ pendingAuthority: signer.publicKey, state: statePda } as any)
+        .signers([signer])
+        .rpc();
+
+    const cancel = () =>
+      program.methods
+        .cancelAuthorityTransfer()
+        .accounts({ authority: authority.publicKey, state: statePda } as any)
+        .signers([authority])
+        .rpc();
+
+    it("只有当前管理员可以提名，只有被提名者可以接受", async () => {
+      await expectError(propose(stranger, stranger.publicKey), "ConstraintHasOne");
+      await expectError(propose(authority, PublicKey.default), "InvalidPendingAuthority");
+
+      await propose(authority, newAuthority.publicKey);
+      const state = await program.account.state.fetch(statePda);
+      expect(state.pendingAuthority.equals(newAuthority.publicKey)).to.be.true;
+      expect(state.authority.equals(authority.publicKey)).to.be.true;
+      await expectError(accept(stranger), "NotPendingAuthority");
+    });
+
+    it("撤销后被提名者不能再接受", async () => {
+      await cancel();
+      expect((await program.account.state.fetch(statePda)).pendingAuthority.equals(PublicKey.default)).to.be.true;
+      await expectError(accept(newAuthority), "NotPendingAuthority");
+      await expectError(cancel(), "NoPendingAuthority");
+    });
+
+    it("被提名者接受后管理员变更，原管理员失去权限", async () => {
+      await propose(authority, newAuthority.publicKey);
+      await accept(newAuthority);
+      const state = await program.account.state.fetch(statePda);
+      expect(state.authority.equals(newAuthority.publicKey)).to.be.true;
+      expect(state.pendingAuthority.equals(PublicKey.default)).to.be.true;
+      await expectError(propose(authority, authority.publicKey), "ConstraintHasOne");
+
+      // 转回原管理员
+      await propose(newAuthority, authority.publicKey);
+      await accept(authority);
+      expect((await program.account.state.fetch(statePda)).authority.equals(authority.publicKey)).to.be.true;
+    });
+  });
+
+  describe("角色权限", () => {
+    let admin: anchor.web3.Keypair;
+    let holder: anchor.web3.Keypair;
+    let stranger: anchor.web3.Keypair;
+
+    before(async () => {
+      await ensureState();
+      admin = await fundedKeypair();
+      holder = await fundedKeypair();
+      stranger = await fundedKeypair();
+    });
+
+    it("合约管理员授予全局管理员，全局管理员授予其他角色", async () => {
+      await grantRole("admin", admin.publicKey);
+      const adminAssignment = await program.account.roleAssignment.fetch(rolePda("admin", admin.publicKey));
+      expect(adminAssignment.role).to.deep.equal({ admin: {} });
+      expect(adminAssignment.holder.equals(admin.publicKey)).to.be.true;
+
+      await grantRole("pauser", holder.publicKey, admin, rolePda("admin", admin.publicKey));
+      expect((await program.account.roleAssignment.fetch(rolePda("pauser", holder.publicKey))).role).to.deep.equal({ pauser: {} });
+
+      // 全局管理员可以执行需要管理员权限的操作
+      const mint = await createMint(provider.connection, authority, authority.publicKey, null, 9);
+      await program.methods
+        .addCurrency(mint)
+        .accounts({
+          authority: admin.publicKey,
+          state: statePda,
+          adminRole: rolePda("admin", admin.publicKey),
+          currencyAllowlist: pda(Buffer.from("currency_allowlist")),
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([admin])
+        .rpc();
+      expect((await program.account.currencyAllowlist.fetch(pda(Buffer.from("currency_allowlist")))).mints.some((allowed) => allowed.equals(mint))).to.be.true;
+    });
+
+    it("全局管理员不能授予全局管理员，无角色账户不能授予角色", async () => {
+      await expectError(
+        grantRole("admin", stranger.publicKey, admin, rolePda("admin", admin.publicKey)),
+        "AdminRoleRestricted"
+      );
+      await expectError(grantRole("operator", stranger.publicKey, stranger), "MissingRole");
+      expect(await provider.connection.getAccountInfo(rolePda("operator", stranger.publicKey))).to.be.null;
+    });
+
+    it("撤销角色关闭角色账户，被撤销的全局管理员失去权限", async () => {
+      await revokeRole("pauser", holder.publicKey, admin, rolePda("admin", admin.publicKey));
+      expect(await provider.connection.getAccountInfo(rolePda("pauser", holder.publicKey))).to.be.null;
+
+      await expectError(
+        revokeRole("admin", admin.publicKey, admin, rolePda("admin", admin.publicKey)),
+        "AdminRoleRestricted"
+      );
+      await revokeRole("admin", admin.publicKey);
+      expect(await provider.connection.getAccountInfo(rolePda("admin", admin.publicKey))).to.be.null;
+      await expectError(grantRole("operator", holder.publicKey, admin), "MissingRole");
+    });
+  });
+
+  describe("全局暂停与赛事暂停", () => {
+    let creator: anchor.web3.Keypair;
+    let bettor: anchor.web3.Keypair;
+    let pauser: anchor.web3.Keypair;
+    let tournament: PublicKey;
+    let round: PublicKey;
+    let team: PublicKey;
+    let placed: { bet: PublicKey; betMint: PublicKey };
+
+    before(async () => {
+      await ensureState();
+      creator = await fundedKeypair();
+      bettor = await fundedKeypair();
+      pauser = await fundedKeypair();
+      tournament = await createTournament(creator, "暂停测试赛事");
+      round = await createRound(creator, tournament, 1);
+      team = await createTeam(creator, tournament, round, "A");
+      await tokenAccount(wanziMint.publicKey, bettor.publicKey, 1_000);
+      placed = await placeBet(bettor, tournament, round, team, 100);
+    });
+
+    // 测试失败时也恢复全局状态，避免影响后续测试
+    after(async () => {
+      if ((await program.account.state.fetch(statePda)).paused) {
+        await setPaused(false);
+      }
+    });
+
+    const setPaused = (paused: boolean) =>
+      program.methods
+        .setPaused(paused)
+        .accounts({ pauser: authority.publicKey, state: statePda, pauserRole: null } as any)
+        .signers([authority])
+        .rpc();
+
+    const setTournamentPaused = (paused: boolean, signer: anchor.web3.Keypair, pauserRole: PublicKey | null) =>
+      program.methods
+        .setTournamentPaused(paused)
+        .accounts({ pauser: signer.publicKey, state: statePda, pauserRole, tournament } as any)
+        .signers([signer])
+        .rpc();
+
+    it("全局暂停期间禁止下注和创建赛事，仍可结算", async () => {
+      await setPaused(true);
+      expect((await program.account.state.fetch(statePda)).paused).to.be.true;
+      await expectError(placeBet(bettor, tournament, round, team, 100), "ProtocolPaused");
+      await expectError(createTournament(await fundedKeypair(), "暂停期间赛事"), "ProtocolPaused");
+
+      // 暂停不影响已有下注的结算
+      await completeRound(creator, tournament, round, team);
+      await program.methods
+        .settleBet()
+        .accounts({
+          tournament,
+          round,
+          winnerTeam: team,
+          bet: placed.bet,
+          user: bettor.publicKey,
+          userBetNft: getAssociatedTokenAddressSync(placed.betMint, bettor.publicKey),
+          betMint: placed.betMint,
+          userCurrencyToken: getAssociatedTokenAddressSync(wanziMint.publicKey, bettor.publicKey),
+          userVoteToken: getAssociatedTokenAddressSync(pda(Buffer.from("vote_mint"), tournament.toBuffer()), bettor.publicKey),
+          currencyVault: pda(Buffer.from("currency_vault"), tournament.toBuffer()),
+          solVault: null,
+          tournamentVoteToken: pda(Buffer.from("tournament_vote"), tournament.toBuffer()),
+          rewardsVault: null,
+          currencyMint: wanziMint.publicKey,
+          voteMint: pda(Buffer.from("vote_mint"), tournament.toBuffer()),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          currencyTokenProgram: TOKEN_PROGRAM_ID,
+          nftTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([bettor])
+        .rpc();
+      expect(await balance(getAssociatedTokenAddressSync(wanziMint.publicKey, bettor.publicKey))).to.equal(1_000);
+
+      await setPaused(false);
+      expect((await program.account.state.fetch(statePda)).paused).to.be.false;
+    });
+
+    it("赛事暂停只影响该赛事，需要合约管理员或暂停管理员", async () => {
+      const nextRound = await createRound(creator, tournament, 2);
+      const nextTeam = await createTeam(creator, tournament, nextRound, "A");
+
+      // 赛事方本人不能暂停
+      await expectError(setTournamentPaused(true, creator, null), "MissingRole");
+      await setTournamentPaused(true, authority, null);
+      expect((await program.account.tournament.fetch(tournament)).paused).to.be.true;
+      await expectError(placeBet(bettor, tournament, nextRound, nextTeam, 100), "TournamentPaused");
+
+      // 持有暂停管理员角色的账户可以恢复赛事
+      await grantRole("pauser", pauser.publicKey);
+      await setTournamentPaused(false, pauser, rolePda("pauser", pauser.publicKey));
+      await placeBet(bettor, tournament, nextRound, nextTeam, 100);
+      expect(await balance(getAssociatedTokenAddressSync(wanziMint.publicKey, bettor.publicKey))).to.equal(900);
+    });
+  });
+
+  describe("赛事创建模式", () => {
+    let admin: anchor.web3.Keypair;
+    let adminRole: PublicKey;
+
+    before(async () => {
+      await ensureState();
+      admin = await fundedKeypair();
+      adminRole = rolePda("admin", admin.publicKey);
+      await grantRole("admin", admin.publicKey);
+    });
+
+    // 恢复为任何人都可以创建赛事，避免影响后续测试
+    after(async () => {
+      await configureCreation({ open: {} }, 0, authority, null);
+    });
+
+    const configureCreation = (mode: object, minStake: number, signer: anchor.web3.Keypair, role: PublicKey | null) =>
+      program.methods
+        .configureTournamentCreation(mode as any, new anchor.BN(minStake))
+        .accounts({ authority: signer.publicKey, state: statePda, adminRole: role } as any)
+        .signers([signer])
+        .rpc();
+
+    it("默认开放创建，只有管理员可以修改创建模式", async () => {
+      expect((await program.account.state.fetch(statePda)).creationMode).to.deep.equal({ open: {} });
+      const stranger = await fundedKeypair();
+      await expectError(configureCreation({ allowlisted: {} }, 0, stranger, null), "MissingRole");
+      await expectError(configureCreation({ minStake: {} }, 0, admin, adminRole), "InvalidStakeAmount");
+      expect((await program.account.state.fetch(statePda)).creationMode).to.deep.equal({ open: {} });
+    });
+
+    it("白名单模式下只有赛事运营可以创建赛事", async () => {
+      await configureCreation({ allowlisted: {} }, 0, admin, adminRole);
+      const operator = await fundedKeypair();
+      await expectError(createTournament(operator, "运营赛事"), "MissingRole");
+
+      await grantRole("operator", operator.publicKey, admin, adminRole);
+      const tournament = await createTournament(operator, "运营赛事", 0, wanziMint.publicKey, rolePda("operator", operator.publicKey));
+      expect((await program.account.tournament.fetch(tournament)).authority.equals(operator.publicKey)).to.be.true;
+    });
+
+    it("最低质押模式下质押不足不能创建，进行中不能赎回到最低质押以下", async () => {
+      await configureCreation({ minStake: {} }, 500, admin, adminRole);
+      const state = await program.account.state.fetch(statePda);
+      expect(state.creationMode).to.deep.equal({ minStake: {} });
+      expect(state.minCreationStake.toNumber()).to.equal(500);
+
+      await expectError(createTournament(await fundedKeypair(), "质押不足赛事", 100), "CreationStakeTooLow");
+
+      const creator = await fundedKeypair();
+      const tournament = await createTournament(creator, "最低质押赛事", 600);
+      const tournamentVoteMint = pda(Buffer.from("vote_mint"), tournament.toBuffer());
+      const unstake = (amount: number) =>
+        program.methods
+          .unstakeTournament(new anchor.BN(amount))
+          .accounts({
+            authority: creator.publicKey,
+            tournament,
+            state: statePda,
+            authorityVoteToken: getAssociatedTokenAddressSync(tournamentVoteMint, creator.publicKey),
+            authorityMatchpToken: getAssociatedTokenAddressSync(matchpMint.publicKey, creator.publicKey),
+            tournamentMatchpToken: pda(Buffer.from("tournament_matchp"), tournament.toBuffer()),
+            voteMint: tournamentVoteMint,
+            stakePosition: pda(Buffer.from("stake_position"), tournament.toBuffer(), creator.publicKey.toBuffer()),
+            matchpMint: matchpMint.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          } as any)
+          .signers([creator])
+          .rpc();
+      await expectError(unstake(200), "CreationStakeTooLow");
+      await unstake(100);
+      expect((await program.account.tournament.fetch(tournament)).stakeAmount.toNumber()).to.equal(500);
+    });
   });
 
   // 暂时注释掉其他测试用例，以便测试能成功运行